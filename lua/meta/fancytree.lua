@@ -21,14 +21,26 @@ local path = {
 ---@return boolean
 local function is_ignored(path) end
 
+---@return string|nil
+---@nodiscard
+local function head() end
+
+---@param path string
+---@return string|nil
+---@nodiscard
+local function relative_path(path) end
+
 ---@class Git
 local git = {
   is_ignored = is_ignored,
+  head = head,
+  relative_path = relative_path,
 }
 
 ---@class FancyTree
 ---@field is_unix boolean
 ---@field os string
+---@field icon_set "nerd"|"emoji"|"unicode"|"ascii"|"none" The set chosen with `--icons`
 ---@field git Git|nil
 fancytree = {
   path = path,
@@ -4,8 +4,18 @@
 ---@field file_type "directory"|"file"|"symlink"
 ---@field is_hidden boolean
 ---@field is_executable boolean
+---@field is_generated boolean
+---@field is_vendored boolean
+---@field is_documentation boolean
+---@field is_virtual boolean The entry doesn't exist on the filesystem (e.g. from `--fromfile`)
 ---@field language string|nil
 
+---@class Charset
+---@field depth string Branch to an entry, like "├── "
+---@field breadth string Column for an ancestor with more entries, like "│   "
+---@field indent string Column for an ancestor that was the last entry, like "    "
+---@field last string Branch to the last entry of a directory, like "└── "
+
 ---@class RGB
 ---@field r integer
 ---@field g integer
@@ -14,4 +24,31 @@
 ---@alias ANSI "black"|"red"|"green"|"yellow"|"blue"|"magenta"|"cyan"|"white"|"bright-black"|"bright-red"|"bright-green"|"bright-yellow"|"bright-blue"|"bright-magenta"|"bright-cyan"|"bright-white"
 ---@alias Color ANSI|RGB
 
+---@class Lookup<T>
+---@field filenames table<string, T>|nil Exact names of files
+---@field directories table<string, T>|nil Exact names of directories
+---@field globs table<string, T>|nil Globs matched against names, longer patterns first
+---@field extensions table<string, T>|nil Extensions, like "rs" or "tar.gz"
+---@field languages table<string, T>|nil Detected language names, like "Rust"
+
 ---@alias GitStatus "added"|"modified"|"removed"|"renamed"
+
+---@class GitStatusCounts
+---@field added integer
+---@field modified integer
+---@field removed integer
+---@field renamed integer
+
+---@class LanguageTotals
+---@field name string
+---@field files integer
+---@field bytes integer
+
+---@class Summary
+---@field directories integer
+---@field files integer
+---@field size integer Total size of the displayed files in bytes
+---@field hidden integer Number of hidden entries that were skipped
+---@field ignored integer Number of git-ignored entries that were skipped
+---@field git { tracked: GitStatusCounts, untracked: GitStatusCounts }
+---@field languages LanguageTotals[] Sorted from most bytes to least
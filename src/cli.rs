@@ -1,29 +1,150 @@
 //! CLI utilities.
+use crate::Error;
 use crate::color::ColorChoice;
 use crate::config::{self, ConfigDir, ConfigFile as _};
-use crate::git::Git;
+use crate::git::{Git, Repositories};
 use crate::lua;
-use crate::tree;
+use crate::tree::{self, CharsetPreset};
 use clap::{Parser, ValueEnum};
+use crossterm::terminal;
 use std::fs;
-use std::path::PathBuf;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
 
 /// Lists files in a directory.
 #[derive(Parser)]
 #[deny(missing_docs)]
 pub struct Cli {
-    /// The path to search in.
+    /// The paths to search in. Each path is shown as its own tree, followed by one
+    /// combined report.
     #[arg(default_value = ".")]
-    pub path: PathBuf,
+    pub paths: Vec<PathBuf>,
+
+    /// Read a newline- or NUL-separated list of paths from the file (or `-` for stdin)
+    /// instead of the filesystem. Paths are relative to the path to search in.
+    #[arg(long, value_name = "FILE|-", num_args = 0..=1, default_missing_value = "-")]
+    pub fromfile: Option<PathBuf>,
+
+    /// Show the merged tree of two directories, marking entries only in A (`-`), only
+    /// in B (`+`), or different (`~`).
+    #[arg(long, num_args = 2, value_names = ["A", "B"], conflicts_with = "fromfile")]
+    pub compare: Option<Vec<PathBuf>>,
+
+    /// How files in both compared directories are compared.
+    #[arg(long, default_value = "content", requires = "compare")]
+    pub compare_by: tree::CompareBy,
+
+    /// Don't show the contents of compared directories without differences.
+    #[arg(long, requires = "compare")]
+    pub collapse_unchanged: bool,
 
     /// Controls colorization.
     #[arg(long = "color")]
     pub color_choice: Option<ColorChoice>,
 
+    /// Controls when file names are written as terminal hyperlinks.
+    #[arg(long, default_value = "auto")]
+    pub hyperlink: tree::HyperlinkChoice,
+
+    /// The characters used to draw the tree.
+    #[arg(long)]
+    pub charset: Option<CharsetPreset>,
+
+    /// The set of icons to use. Use `none` to remove the icons.
+    #[arg(long, default_value = "nerd")]
+    pub icons: config::IconSet,
+
     /// Go only this many levels deep.
     #[arg(short = 'L', long)]
     pub level: Option<usize>,
 
+    /// List directories only.
+    #[arg(short = 'd', long)]
+    pub dirs_only: bool,
+
+    /// Print each entry's path relative to the root instead of its file name.
+    #[arg(short = 'f', long)]
+    pub full_path: bool,
+
+    /// Don't print the indentation lines.
+    #[arg(short = 'i', long)]
+    pub no_indent: bool,
+
+    /// Don't descend into directories with more than this many entries.
+    #[arg(long)]
+    pub filelimit: Option<usize>,
+
+    /// Show at most this many entries for each directory.
+    #[arg(long)]
+    pub max_entries: Option<usize>,
+
+    /// Replace the middle of names that are too wide for the terminal with an
+    /// ellipsis.
+    #[arg(long, conflicts_with = "wrap")]
+    pub truncate: bool,
+
+    /// Continue names that are too wide for the terminal on the next line.
+    #[arg(long)]
+    pub wrap: bool,
+
+    /// Descend into archives (e.g. `.tar.gz` and `.zip`) like directories. An archive
+    /// passed as the path is always opened.
+    #[arg(long)]
+    pub archives: bool,
+
+    /// Hide generated files.
+    #[arg(long)]
+    pub hide_generated: bool,
+
+    /// Hide vendored files.
+    #[arg(long)]
+    pub hide_vendored: bool,
+
+    /// Hide documentation files.
+    #[arg(long)]
+    pub hide_documentation: bool,
+
+    /// Show language statistics for the displayed files.
+    #[arg(long, num_args = 0..=1, default_missing_value = "report")]
+    pub languages: Option<tree::LanguagesMode>,
+
+    /// Don't show the summary report after the tree.
+    #[arg(long)]
+    pub noreport: bool,
+
+    /// Browse the tree interactively, and print the selected path on exit (e.g.
+    /// `cd "$(fancy-tree --interactive)"`).
+    #[arg(long)]
+    pub interactive: bool,
+
+    /// Keep the tree on screen, re-writing it when files change.
+    #[arg(long, conflicts_with_all = ["fromfile", "compare", "interactive"])]
+    pub watch: bool,
+
+    /// Abort on the first error raised by a configuration callback, instead of
+    /// falling back to the default and reporting the error afterwards.
+    #[arg(long)]
+    pub strict_config: bool,
+
+    /// Use the configuration files in this directory instead of the default directory.
+    /// Defaults to `$FANCY_TREE_CONFIG_DIR` if it's set.
+    #[arg(long, value_name = "DIR")]
+    pub config_dir: Option<PathBuf>,
+
+    /// Don't load any configuration files, including project configurations.
+    #[arg(long, conflicts_with_all = ["config_dir", "trust", "check_config", "edit_config"])]
+    pub no_config: bool,
+
+    /// Trust the project configuration (`.fancy-tree/` or `.fancy-tree.lua`) found for
+    /// each path, so that it's loaded on top of the user configuration. Changed
+    /// project configurations need to be trusted again.
+    #[arg(long)]
+    pub trust: bool,
+
+    /// Check the configuration files for problems and exit.
+    #[arg(long, conflicts_with = "edit_config")]
+    pub check_config: bool,
+
     /// Edit the main configuration file and exit.
     #[arg(long, num_args = 0..=1, default_missing_value = "config")]
     pub edit_config: Option<EditConfig>,
@@ -38,68 +159,201 @@ pub enum EditConfig {
     Icons,
     /// The custom colors configuration.
     Colors,
+    /// The unified configuration, which replaces the other files. If it doesn't exist,
+    /// it's generated from the other files.
+    Init,
 }
 
 impl Cli {
     /// An environment variable the user can set to specify which editor to use.
     const EDITOR_ENV_VAR: &str = "FANCY_TREE_EDITOR";
 
+    /// The `--fromfile` value to read from stdin.
+    const STDIN_PATH: &str = "-";
+
     /// Runs the CLI.
     pub fn run(&self) -> crate::Result {
         // NOTE Early return for edit mode
         if let Some(edit_config) = self.edit_config {
             return self.edit_file(edit_config);
         }
+        if self.check_config {
+            return self.check_config();
+        }
 
         self.run_tree()
     }
 
     /// Runs the main tree functionality.
     fn run_tree(&self) -> crate::Result {
-        let git = Git::new(&self.path).expect("Should be able to read the git repository");
+        // NOTE When comparing, the merged tree is rooted at the second directory.
+        let roots = match self.compare {
+            Some(ref paths) => &paths[1..],
+            None => self.paths.as_slice(),
+        };
+        if roots.len() > 1 && (self.fromfile.is_some() || self.interactive || self.watch) {
+            return Err(Error::Usage(
+                "--fromfile, --interactive, and --watch only support one path",
+            ));
+        }
+
+        // NOTE Compared entries show their differences instead of git statuses.
+        let mut repositories = Repositories::default();
+        let repository_indices = roots
+            .iter()
+            .map(|root| {
+                if self.compare.is_some() {
+                    return None;
+                }
+                // NOTE An unreadable repository only loses the git decorations.
+                repositories.discover(root).unwrap_or_else(|err| {
+                    eprintln!(
+                        "warning: ignoring the git repository of `{}`: {err}",
+                        root.display()
+                    );
+                    None
+                })
+            })
+            .collect::<Vec<_>>();
+        let gits = repository_indices
+            .into_iter()
+            .map(|index| index.and_then(|index| repositories.get(index)))
+            .collect::<Vec<_>>();
 
         // NOTE The Lua state must live as long as the configuration values.
         let lua_state = {
-            let mut builder = lua::state::Builder::new();
-            if let Some(ref git) = git {
+            let mut builder = lua::state::Builder::new().with_icon_set(self.icons);
+            if let Some(git) = gits.iter().flatten().next() {
                 builder = builder.with_git(git);
             }
-            builder.build().expect("The lua state should be valid")
+            builder.build()?
         };
 
-        // TODO Skip loading the config instead of panicking.
-        let config_dir = ConfigDir::new().expect("A config dir should be available");
-
         let lua_inner = lua_state.to_inner();
-        let config = config_dir
-            .load_main(lua_inner)
-            .expect("The configuration should be valid");
-        let icons = config_dir
-            .load_icons(lua_inner)
-            .expect("The icon configuration should be valid");
-        let colors = config_dir
-            .load_colors(lua_inner)
-            .expect("The color configuration should be valid");
-
-        let mut builder = tree::Builder::new(&self.path);
+        let errors = config::ErrorLog::new(self.strict_config);
+        errors.install(lua_inner);
+
+        // NOTE Without a config directory, the defaults are used.
+        let config_dir = if self.no_config {
+            None
+        } else {
+            self.config_dir().ok()
+        };
+        let user_configs = match config_dir {
+            Some(ref config_dir) => config_dir.load(lua_inner)?,
+            None => config::Configs::default(),
+        };
+
+        let mut trees = Vec::with_capacity(roots.len());
+        for (root, git) in roots.iter().zip(gits) {
+            let project_configs = if self.no_config {
+                None
+            } else {
+                self.project_configs(root, git, config_dir.as_ref(), lua_inner)?
+            };
+            let configs = match project_configs {
+                Some(project_configs) => user_configs.clone().merge(project_configs),
+                None => user_configs.clone(),
+            };
+            let builder = self
+                .tree_builder(root, git, &configs)?
+                .error_log(errors.clone());
+            trees.push((builder.build(), git));
+        }
+
+        let result = self.write_trees(&lua_state, &mut trees);
+        // NOTE Errors are reported after the tree so that they aren't mixed into it.
+        errors.write_report(&mut io::stderr())?;
+        if errors.is_strict() && !errors.is_empty() {
+            return Err(Error::StrictConfig);
+        }
+        result
+    }
+
+    /// Writes the trees, or browses or watches the first tree.
+    fn write_trees(
+        &self,
+        lua_state: &lua::state::State,
+        trees: &mut [(tree::Tree<'_, '_, &PathBuf>, Option<&Git>)],
+    ) -> crate::Result {
+        if self.interactive {
+            let (ref tree, _) = trees[0];
+            let selected = lua_state.in_git_scope(|| tree.browse().map_err(Error::from))?;
+            if let Some(selected) = selected {
+                println!("{}", selected.display());
+            }
+            return Ok(());
+        }
+
+        if self.watch {
+            let (ref mut tree, _) = trees[0];
+            lua_state.in_git_scope(|| tree.watch())?;
+            return Ok(());
+        }
+
+        let mut stdout = io::stdout();
+        let mut summary = tree::Summary::new();
+        for (tree, git) in trees.iter() {
+            let tree_summary = lua_state
+                .in_scope_of_git(*git, || tree.write_tree(&mut stdout).map_err(Error::from))?;
+            summary.merge(&tree_summary);
+        }
+        // NOTE The report settings are the same for every tree.
+        if let Some((tree, git)) = trees.last() {
+            lua_state.in_scope_of_git(*git, || {
+                tree.write_report(&mut stdout, &summary)
+                    .map_err(Error::from)
+            })?;
+        }
+        stdout.flush()?;
+
+        Ok(())
+    }
+
+    /// Creates the builder for the tree at the root.
+    fn tree_builder<'git, 'root>(
+        &self,
+        root: &'root PathBuf,
+        git: Option<&'git Git>,
+        configs: &config::Configs,
+    ) -> crate::Result<tree::Builder<'git, 'static, &'root PathBuf>> {
+        let mut builder = tree::Builder::new(root);
+        let config = &configs.main;
 
         // NOTE Apply configuration overrides from CLI.
         if let Some(color_choice) = self.color_choice {
             builder = builder.color_choice(color_choice);
+        } else if self.interactive
+            && config
+                .as_ref()
+                .is_none_or(|config| config.color_choice().is_auto())
+            && io::stderr().is_terminal()
+        {
+            // NOTE The browser is drawn on stderr, but automatic colors check stdout,
+            //      which is usually captured to get the selected path.
+            builder = builder.color_choice(ColorChoice::On);
+        }
+
+        // NOTE The CLI charset takes priority over the configured charset.
+        let charset = self
+            .charset
+            .map(tree::Charset::from)
+            .or_else(|| config.as_ref().and_then(|config| config.charset().cloned()));
+        if let Some(charset) = charset {
+            builder = builder.charset(charset);
         }
 
         // NOTE Apply configurations if they exist
         if let Some(config) = config {
-            builder = builder.config(config);
-        }
-        if let Some(icons) = icons {
-            builder = builder.icons(icons);
+            builder = builder.config(config.clone());
         }
-        if let Some(colors) = colors {
-            builder = builder.colors(colors);
+        let icons = configs.icons.clone().unwrap_or_default();
+        builder = builder.icons(icons.with_icon_set(self.icons));
+        if let Some(ref colors) = configs.colors {
+            builder = builder.colors(colors.clone());
         }
 
-        if let Some(ref git) = git {
+        if let Some(git) = git {
             builder = builder.git(git);
         }
 
@@ -107,30 +361,183 @@ impl Cli {
             builder = builder.max_level(level);
         }
 
-        let tree = builder.build();
+        if let Some(filelimit) = self.filelimit {
+            builder = builder.filelimit(filelimit);
+        }
+        if let Some(max_entries) = self.max_entries {
+            builder = builder.max_entries(max_entries);
+        }
 
-        lua_state.in_git_scope(|| tree.write_to_stdout().map_err(mlua::Error::external))?;
+        if let Some(languages) = self.languages {
+            builder = builder.languages(languages);
+        }
 
-        Ok(())
+        // NOTE Output that isn't written to a terminal has no width to fit.
+        let overflow = if self.truncate {
+            Some(tree::Overflow::Truncate)
+        } else if self.wrap {
+            Some(tree::Overflow::Wrap)
+        } else {
+            None
+        };
+        if let Some(overflow) = overflow
+            && io::stdout().is_terminal()
+            && let Ok((width, _)) = terminal::size()
+        {
+            builder = builder.fit_to_width(usize::from(width), overflow);
+        }
+
+        if let Some(ref paths) = self.compare {
+            let comparison = tree::Comparison::new(
+                &paths[0],
+                &paths[1],
+                self.compare_by,
+                self.collapse_unchanged,
+                self.level,
+            );
+            builder = builder.comparison(comparison);
+        }
+
+        if let Some(ref fromfile) = self.fromfile {
+            let listing = self.read_listing(root, fromfile)?;
+            builder = builder.listing(listing);
+        }
+
+        let builder = builder
+            .directories_only(self.dirs_only)
+            .full_path(self.full_path)
+            .archives(self.archives)
+            .hyperlink(self.hyperlink)
+            .indent(!self.no_indent)
+            .report(!self.noreport)
+            .hide_generated(self.hide_generated)
+            .hide_vendored(self.hide_vendored)
+            .hide_documentation(self.hide_documentation);
+        Ok(builder)
+    }
+
+    /// Loads the configuration of the project the root is in, if it's trusted or
+    /// `--trust` is used. The project is searched for up to the root of the git
+    /// worktree.
+    fn project_configs(
+        &self,
+        root: &Path,
+        git: Option<&Git>,
+        config_dir: Option<&ConfigDir>,
+        lua: &mlua::Lua,
+    ) -> crate::Result<Option<config::Configs>> {
+        let boundary = git.and_then(Git::root_dir);
+        let Some(project) = config::ProjectConfig::discover(root, boundary) else {
+            return Ok(None);
+        };
+        let trust_store = config_dir.map(ConfigDir::trust_store);
+
+        let is_trusted = match trust_store {
+            Some(ref trust_store) if self.trust => {
+                trust_store.trust(&project)?;
+                true
+            }
+            Some(ref trust_store) => trust_store.is_trusted(&project)?,
+            None => false,
+        };
+        if !is_trusted {
+            // NOTE Project configurations run the project's code, so they're skipped
+            //      until the user reviews and trusts them.
+            eprintln!(
+                "warning: ignoring the untrusted project configuration `{}` (run with --trust to trust it)",
+                project.path().display()
+            );
+            return Ok(None);
+        }
+        project.load(lua).map(Some)
+    }
+
+    /// Reads the listing of paths from the file, or from stdin.
+    fn read_listing(&self, root: &Path, fromfile: &Path) -> crate::Result<tree::Listing> {
+        let text = if fromfile == Path::new(Self::STDIN_PATH) {
+            let mut text = Vec::new();
+            io::stdin().read_to_end(&mut text)?;
+            text
+        } else {
+            fs::read(fromfile).map_err(|source| Error::ReadFile {
+                path: fromfile.to_path_buf(),
+                source,
+            })?
+        };
+        Ok(tree::Listing::parse(root, &text))
+    }
+
+    /// Gets the configuration directory from `--config-dir`, or the default directory.
+    fn config_dir(&self) -> crate::Result<ConfigDir> {
+        match self.config_dir {
+            Some(ref path) => Ok(ConfigDir::from_path(path)),
+            None => ConfigDir::new(),
+        }
+    }
+
+    /// Checks the configuration files, and fails if there are problems.
+    fn check_config(&self) -> crate::Result {
+        // NOTE Callbacks can use the git utilities, so they're available if the current
+        //      directory is in a repository. An unreadable repository only loses the
+        //      git utilities.
+        let git = Git::new(".").unwrap_or_else(|err| {
+            eprintln!("warning: ignoring the git repository of `.`: {err}");
+            None
+        });
+        let lua_state = {
+            let mut builder = lua::state::Builder::new().with_icon_set(self.icons);
+            if let Some(ref git) = git {
+                builder = builder.with_git(git);
+            }
+            builder.build()?
+        };
+        let config_dir = self.config_dir()?;
+
+        let report =
+            lua_state.in_git_scope(|| Ok::<_, Error>(config_dir.check(lua_state.to_inner())))?;
+        report.write_to(&mut io::stdout())?;
+        if report.is_ok() {
+            return Ok(());
+        }
+        Err(Error::ConfigProblems(report.problem_count()))
     }
 
     /// Opens an editor for the file the user specified, creating the config directory
     /// if needed.
     fn edit_file(&self, edit_config: EditConfig) -> crate::Result {
-        let config_dir = ConfigDir::new()?;
+        let config_dir = self.config_dir()?;
         fs::create_dir_all(config_dir.path())?;
 
         let (file_path, default_contents) = match edit_config {
             EditConfig::Config => (config_dir.main_path(), config::Main::DEFAULT_MODULE),
             EditConfig::Icons => (config_dir.icons_path(), config::Icons::DEFAULT_MODULE),
             EditConfig::Colors => (config_dir.colors_path(), config::Colors::DEFAULT_MODULE),
+            EditConfig::Init => (config_dir.init_path(), config::Configs::DEFAULT_MODULE),
         };
 
         // NOTE If we can't check if it exists, we'll be safe and skip overwriting it.
         if !file_path.try_exists().unwrap_or(false) {
+            let migrated = match edit_config {
+                EditConfig::Init => config_dir.migrate_to_init()?,
+                _ => None,
+            };
+            if migrated.is_some() {
+                println!(
+                    "Migrating the configuration files to `{}`, the other files are ignored while it exists",
+                    file_path.display()
+                );
+            }
+            let contents = migrated.as_deref().unwrap_or(default_contents);
             // NOTE Ignore error, because editing the file is a higher priority than
             //      writing to it.
-            let _ = fs::write(&file_path, default_contents);
+            let _ = fs::write(&file_path, contents);
+        }
+        if !matches!(edit_config, EditConfig::Init) && config_dir.init_path().exists() {
+            eprintln!(
+                "warning: `{}` is ignored because `{}` exists",
+                file_path.display(),
+                config_dir.init_path().display()
+            );
         }
 
         println!("Opening `{}`", file_path.display());
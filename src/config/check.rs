@@ -0,0 +1,496 @@
+//! Module for checking configuration files for problems.
+use super::errors::location;
+use super::modules;
+use super::{Colors, ConfigDir, ConfigFile, Configs, ErrorLog, Icons, Main};
+use crate::color::ColorChoice;
+use crate::git::status::Status;
+use crate::tree::entry::Attributes;
+use crate::tree::{Charset, Entry, Summary};
+use mlua::{FromLua, Lua};
+use std::fmt::{self, Display};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// The expected shape of a configuration value.
+enum Shape {
+    /// A function.
+    Function,
+    /// A [`ColorChoice`] name.
+    ColorChoice,
+    /// A [`Charset`] preset name or table.
+    Charset,
+    /// A table with these keys. Every key is optional.
+    Table(&'static [(&'static str, Shape)]),
+    /// A table with any keys.
+    Map,
+    /// A function, or a lookup table with a fallback function.
+    Lookup,
+}
+
+impl Shape {
+    /// The expected shape of a lookup table.
+    const LOOKUP_TABLE: Shape = Shape::Table(&[
+        ("filenames", Shape::Map),
+        ("directories", Shape::Map),
+        ("globs", Shape::Map),
+        ("extensions", Shape::Map),
+        ("languages", Shape::Map),
+        ("fallback", Shape::Function),
+    ]);
+}
+
+/// A configuration file that can be checked.
+trait Checked: ConfigFile + FromLua {
+    /// The expected shape of the value returned by the file.
+    const SHAPE: Shape;
+    /// Can the file return `nil`?
+    const NIL_ALLOWED: bool = false;
+
+    /// Calls each callback with the entries.
+    fn exercise(&self, entries: &[Entry<PathBuf>], summary: &Summary);
+}
+
+impl Checked for Main {
+    const SHAPE: Shape = Shape::Table(&[
+        ("color", Shape::ColorChoice),
+        ("charset", Shape::Charset),
+        ("skip", Shape::Function),
+        ("on_finish", Shape::Function),
+        ("filelimit", Shape::Function),
+        ("max_entries", Shape::Function),
+        ("hyperlink", Shape::Function),
+        ("sort", Shape::Function),
+    ]);
+
+    fn exercise(&self, entries: &[Entry<PathBuf>], summary: &Summary) {
+        for entry in entries {
+            self.should_skip(entry, || entry.is_hidden());
+            self.hyperlink(entry, None);
+            self.sort_key(entry);
+            if entry.attributes().is_directory() {
+                self.filelimit(entry, None);
+                self.max_entries(entry, None);
+            }
+        }
+        self.format_summary(summary);
+    }
+}
+
+impl Checked for Icons {
+    const SHAPE: Shape = Shape::Lookup;
+    const NIL_ALLOWED: bool = true;
+
+    fn exercise(&self, entries: &[Entry<PathBuf>], _summary: &Summary) {
+        for entry in entries {
+            self.get_icon(entry, entry.attributes().is_directory());
+        }
+    }
+}
+
+impl Checked for Colors {
+    const SHAPE: Shape = Shape::Table(&[
+        ("icons", Shape::Lookup),
+        (
+            "git_statuses",
+            Shape::Table(&[("tracked", Shape::Function), ("untracked", Shape::Function)]),
+        ),
+    ]);
+
+    fn exercise(&self, entries: &[Entry<PathBuf>], _summary: &Summary) {
+        for entry in entries {
+            self.for_icon(entry);
+        }
+        for status in Status::ALL {
+            let subject = format_args!("the {status:?} status");
+            self.for_tracked_git_status(status, subject);
+            self.for_untracked_git_status(status, subject);
+        }
+    }
+}
+
+impl Checked for Configs {
+    const SHAPE: Shape = Shape::Table(&[
+        ("color", Shape::ColorChoice),
+        ("charset", Shape::Charset),
+        ("skip", Shape::Function),
+        ("on_finish", Shape::Function),
+        ("filelimit", Shape::Function),
+        ("max_entries", Shape::Function),
+        ("hyperlink", Shape::Function),
+        ("sort", Shape::Function),
+        ("icons", Shape::Lookup),
+        ("colors", Shape::Lookup),
+        (
+            "git_statuses",
+            Shape::Table(&[("tracked", Shape::Function), ("untracked", Shape::Function)]),
+        ),
+    ]);
+
+    fn exercise(&self, entries: &[Entry<PathBuf>], summary: &Summary) {
+        if let Some(ref main) = self.main {
+            main.exercise(entries, summary);
+        }
+        if let Some(ref icons) = self.icons {
+            icons.exercise(entries, summary);
+        }
+        if let Some(ref colors) = self.colors {
+            colors.exercise(entries, summary);
+        }
+    }
+}
+
+/// A problem with the shape of a configuration file.
+#[derive(Debug)]
+struct Problem {
+    /// The file, or the file and line, of the problem.
+    location: String,
+    /// What's wrong.
+    message: String,
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// The problems found by checking the configuration files.
+#[derive(Debug)]
+pub struct CheckReport {
+    /// The configuration files that were checked.
+    checked: Vec<PathBuf>,
+    /// Problems with loading the files and their shapes.
+    problems: Vec<Problem>,
+    /// Errors raised by the callbacks.
+    errors: ErrorLog,
+}
+
+impl CheckReport {
+    /// Were no problems found?
+    #[inline]
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty() && self.errors.is_empty()
+    }
+
+    /// Gets the number of problems found.
+    #[inline]
+    pub fn problem_count(&self) -> usize {
+        self.problems.len() + self.errors.len()
+    }
+
+    /// Writes the problems, and which files were checked.
+    pub fn write_to<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: Write,
+    {
+        for problem in self.problems.iter() {
+            writeln!(writer, "{problem}")?;
+        }
+        self.errors.write_report(writer)?;
+        if self.checked.is_empty() {
+            writeln!(
+                writer,
+                "No configuration files found, the defaults are used"
+            )?;
+        }
+        for path in self.checked.iter() {
+            writeln!(writer, "Checked `{}`", path.display())?;
+        }
+        Ok(())
+    }
+}
+
+impl ConfigDir {
+    /// Checks the configuration files.
+    ///
+    /// Each file is validated against the shape it's expected to have, and then its
+    /// callbacks are called with a set of made-up entries (a file, a directory, a
+    /// symlink, an executable, and each git status). If there's a unified `init.lua`,
+    /// the separate files are reported as ignored.
+    pub fn check(&self, lua: &Lua) -> CheckReport {
+        let errors = ErrorLog::default();
+        errors.install(lua);
+        let mut report = CheckReport {
+            checked: Vec::new(),
+            problems: Vec::new(),
+            errors,
+        };
+        let entries = synthetic_entries();
+        let mut summary = Summary::new();
+        for entry in entries.iter() {
+            summary.add_entry(entry, Some(Status::Added), Some(Status::Modified));
+        }
+
+        if !self.init_path().exists() {
+            self.check_file::<Main>(lua, &mut report, &entries, &summary);
+            self.check_file::<Icons>(lua, &mut report, &entries, &summary);
+            self.check_file::<Colors>(lua, &mut report, &entries, &summary);
+            return report;
+        }
+
+        self.check_file::<Configs>(lua, &mut report, &entries, &summary);
+        for path in [self.main_path(), self.icons_path(), self.colors_path()] {
+            if path.exists() {
+                report.problems.push(Problem {
+                    location: path.display().to_string(),
+                    message: format!("ignored because `{}` exists", Configs::FILENAME),
+                });
+            }
+        }
+        report
+    }
+
+    /// Checks a configuration file, if it exists.
+    fn check_file<T>(
+        &self,
+        lua: &Lua,
+        report: &mut CheckReport,
+        entries: &[Entry<PathBuf>],
+        summary: &Summary,
+    ) where
+        T: Checked,
+    {
+        let path = self.file_name::<T>();
+        if !path.exists() {
+            return;
+        }
+        report.checked.push(path.clone());
+
+        let lib_dir = self.path().join(modules::LIB_DIR);
+        let value = modules::environment(lua, Some(lib_dir)).and_then(|env| {
+            lua.load(path.as_path())
+                .set_environment(env)
+                .call::<mlua::Value>(())
+        });
+        let value = match value {
+            Ok(value) => value,
+            Err(err) => {
+                // NOTE Lua errors already include the file and line.
+                report.problems.push(Problem {
+                    location: path.display().to_string(),
+                    message: err.to_string(),
+                });
+                return;
+            }
+        };
+
+        let problems_before = report.problems.len();
+        if !(value.is_nil() && T::NIL_ALLOWED) {
+            check_shape(
+                lua,
+                &path,
+                T::FILENAME,
+                &value,
+                &T::SHAPE,
+                &mut report.problems,
+            );
+        }
+
+        // NOTE Callbacks are still called if there are only unknown keys, but the
+        //      conversion error isn't repeated if the shape is wrong.
+        match T::from_lua(value, lua) {
+            Ok(config) => config.exercise(entries, summary),
+            Err(_) if report.problems.len() > problems_before => {}
+            Err(err) => report.problems.push(Problem {
+                location: path.display().to_string(),
+                message: err.to_string(),
+            }),
+        }
+    }
+}
+
+/// Checks that the value has the shape, adding any problems. `name` is the name of
+/// the value, like `config.lua` or `git_statuses.tracked`.
+fn check_shape(
+    lua: &Lua,
+    path: &Path,
+    name: &str,
+    value: &mlua::Value,
+    shape: &Shape,
+    problems: &mut Vec<Problem>,
+) {
+    let problem = |message: String| Problem {
+        location: path.display().to_string(),
+        message: format!("`{name}`: {message}"),
+    };
+
+    match shape {
+        Shape::Function if value.is_function() => {}
+        Shape::Function => problems.push(problem(format!(
+            "expected a function, got {}",
+            value.type_name()
+        ))),
+        Shape::Map if value.is_table() => {}
+        Shape::Map => problems.push(problem(format!(
+            "expected a table, got {}",
+            value.type_name()
+        ))),
+        Shape::Lookup if value.is_function() => {}
+        Shape::Lookup if value.is_table() => {
+            check_shape(lua, path, name, value, &Shape::LOOKUP_TABLE, problems);
+        }
+        Shape::Lookup => problems.push(problem(format!(
+            "expected a function or a table, got {}",
+            value.type_name()
+        ))),
+        Shape::ColorChoice => {
+            if let Err(err) = ColorChoice::from_lua(value.clone(), lua) {
+                problems.push(problem(err.to_string()));
+            }
+        }
+        Shape::Charset => {
+            if let Err(err) = Charset::from_lua(value.clone(), lua) {
+                problems.push(problem(err.to_string()));
+            }
+        }
+        Shape::Table(keys) => {
+            let Some(table) = value.as_table() else {
+                problems.push(problem(format!(
+                    "expected a table, got {}",
+                    value.type_name()
+                )));
+                return;
+            };
+            let pairs = table
+                .pairs::<mlua::Value, mlua::Value>()
+                .collect::<mlua::Result<Vec<_>>>();
+            let pairs = match pairs {
+                Ok(pairs) => pairs,
+                Err(err) => {
+                    problems.push(problem(err.to_string()));
+                    return;
+                }
+            };
+            let mut pairs = pairs
+                .into_iter()
+                .map(|(key, value)| {
+                    let key_name = match key.as_string() {
+                        Some(key) => key.to_string_lossy(),
+                        None => format!("[{}]", key.to_string().unwrap_or_default()),
+                    };
+                    (key_name, value)
+                })
+                .collect::<Vec<_>>();
+            // NOTE Table order is arbitrary, but problems should be in a stable order.
+            pairs.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            for (key_name, value) in pairs {
+                let qualified_name = if name.ends_with(".lua") {
+                    key_name.clone()
+                } else {
+                    format!("{name}.{key_name}")
+                };
+
+                let Some((_, shape)) = keys.iter().find(|(key, _)| *key == key_name) else {
+                    // NOTE A function's location points to the key better than the file.
+                    let location = value
+                        .as_function()
+                        .and_then(location)
+                        .unwrap_or_else(|| path.display().to_string());
+                    problems.push(Problem {
+                        location,
+                        message: format!("unknown key `{qualified_name}`"),
+                    });
+                    continue;
+                };
+                check_shape(lua, path, &qualified_name, &value, shape, problems);
+            }
+        }
+    }
+}
+
+/// Creates made-up entries to call the callbacks with.
+fn synthetic_entries() -> Vec<Entry<PathBuf>> {
+    /// Made-up files, with their contents, and if they're executable.
+    const FILES: [(&str, &str, bool); 4] = [
+        ("./src/main.rs", "fn main() {}\n", false),
+        ("./README.md", "# Project\n", false),
+        ("./.gitignore", "/target\n", false),
+        ("./scripts/build.sh", "#!/bin/sh\n", true),
+    ];
+
+    let files = FILES.into_iter().map(|(path, contents, executable)| {
+        let path = PathBuf::from(path);
+        // NOTE Reading from a slice can't fail.
+        let attributes = Attributes::new_archived_file(
+            &path,
+            &path,
+            contents.as_bytes(),
+            contents.len() as u64,
+            executable,
+        )
+        .expect("Reading from a slice should succeed");
+        Entry::with_attributes(path, attributes)
+    });
+    let others = [
+        Entry::with_attributes(PathBuf::from("./src"), Attributes::new_archived_directory()),
+        Entry::with_attributes(PathBuf::from("./link"), Attributes::new_symlink(false)),
+    ];
+    files.chain(others).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks the shape of the module's value, and gets the problem messages.
+    fn shape_problems<T>(module: &str) -> Vec<String>
+    where
+        T: Checked,
+    {
+        let lua = Lua::new();
+        let value = lua
+            .load(module)
+            .call::<mlua::Value>(())
+            .expect("The module should run");
+        let mut problems = Vec::new();
+        check_shape(
+            &lua,
+            Path::new(T::FILENAME),
+            T::FILENAME,
+            &value,
+            &T::SHAPE,
+            &mut problems,
+        );
+        problems
+            .into_iter()
+            .map(|problem| problem.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_default_modules_have_valid_shapes() {
+        assert!(shape_problems::<Main>(Main::DEFAULT_MODULE).is_empty());
+        assert!(shape_problems::<Icons>(Icons::DEFAULT_MODULE).is_empty());
+        assert!(shape_problems::<Colors>(Colors::DEFAULT_MODULE).is_empty());
+        assert!(shape_problems::<Configs>(Configs::DEFAULT_MODULE).is_empty());
+    }
+
+    #[test]
+    fn test_check_shape_problems() {
+        let module = r#"
+            return {
+                color = "sometimes",
+                skp = 1,
+                git_statuses = {},
+            }
+        "#;
+        let problems = shape_problems::<Main>(module);
+        assert_eq!(3, problems.len());
+        assert!(problems[0].starts_with("config.lua: `color`: "));
+        assert_eq!("config.lua: unknown key `git_statuses`", problems[1]);
+        assert_eq!("config.lua: unknown key `skp`", problems[2]);
+
+        let module = "return { filenames = {}, extension = {} }";
+        assert_eq!(
+            vec!["icons.lua: unknown key `extension`"],
+            shape_problems::<Icons>(module)
+        );
+
+        let module = "return { git_statuses = { tracked = 'red' } }";
+        assert_eq!(
+            vec!["colors.lua: `git_statuses.tracked`: expected a function, got string"],
+            shape_problems::<Colors>(module)
+        );
+    }
+}
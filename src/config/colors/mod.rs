@@ -1,5 +1,6 @@
 //! Module for configuring colors.
-use super::ConfigFile;
+use super::lookup::Lookup;
+use super::{ConfigFile, ErrorLog};
 use crate::color::Color;
 use crate::git::status::{self, Status};
 use crate::lua::interop;
@@ -9,14 +10,21 @@ use crate::tree::{
 };
 use mlua::{FromLua, Lua};
 use owo_colors::AnsiColors;
+use std::fmt::Display;
 use std::path::Path;
 
 /// The configuration for application colors.
-#[derive(Debug, Default)]
+///
+/// Like [`super::Main`], configurations can be layered with [`Colors::merge`].
+#[derive(Debug, Default, Clone)]
 pub struct Colors {
-    /// Function to get the color for an entry's icon.
-    for_icon: Option<mlua::Function>,
+    /// Icon colors matched before the functions are called.
+    icon_lookup: Lookup<Color>,
+    /// Functions to get the color for an entry's icon.
+    for_icon: Vec<mlua::Function>,
     git_statuses: GitStatuses,
+    /// Collects errors raised by the functions.
+    errors: ErrorLog,
 }
 
 impl Colors {
@@ -30,35 +38,69 @@ impl Colors {
     const DEFAULT_SYMLINK_COLOR: Option<Color> = Some(Color::Ansi(AnsiColors::Cyan));
 
     /// Get the color for an entry's icon.
+    ///
+    /// Entries that match the color table use its color, and the functions are only
+    /// called for the rest.
     pub fn for_icon<P>(&self, entry: &Entry<P>) -> Option<Color>
     where
         P: AsRef<Path>,
     {
+        // NOTE Matched entries don't need a Lua function call.
+        if let Some(color) = self.icon_lookup.get(entry) {
+            return Some(*color);
+        }
         let path = entry.path();
         let default: Option<Color> = match entry.attributes() {
             Attributes::Directory(_) => Self::DEFAULT_DIRECTORY_COLOR,
             Attributes::File(attributes) => Self::get_file_color(attributes),
             Attributes::Symlink(_) => Self::DEFAULT_SYMLINK_COLOR,
+            Attributes::Virtual(attributes) if attributes.is_directory() => {
+                Self::DEFAULT_DIRECTORY_COLOR
+            }
+            Attributes::Virtual(_) => Self::DEFAULT_FILE_COLOR,
         };
-        let attributes = interop::FileAttributes::from(entry);
+        let mut color = default;
+        for f in self.for_icon.iter() {
+            let attributes = interop::FileAttributes::from(entry);
+            let result = f.call::<Option<Color>>((path, attributes, color));
+            color = self
+                .errors
+                .unwrap_or(result, "colors.icons", f, path.display(), color);
+        }
+        color
+    }
 
-        // TODO Report error
-        self.for_icon
-            .as_ref()
-            .map_or(Ok(default), |f| {
-                f.call::<Option<Color>>((path, attributes, default))
-            })
-            .unwrap_or(default)
+    /// Merges a configuration on top of this one. The functions of `other` get the
+    /// results of this configuration's functions as their `default`.
+    #[must_use]
+    pub fn merge(mut self, other: Self) -> Self {
+        self.icon_lookup = self.icon_lookup.merge(other.icon_lookup);
+        self.for_icon.extend(other.for_icon);
+        self.git_statuses.tracked.extend(other.git_statuses.tracked);
+        self.git_statuses
+            .untracked
+            .extend(other.git_statuses.untracked);
+        self
     }
 
-    /// Get the color for an untracked file's status.
-    pub fn for_untracked_git_status(&self, status: Status) -> Option<Color> {
-        self.git_statuses.get_untracked_color(status)
+    /// Get the color for an untracked file's status. `subject` is what the status is
+    /// for (like the entry's path), which is reported if a callback raises an error.
+    pub fn for_untracked_git_status<S>(&self, status: Status, subject: S) -> Option<Color>
+    where
+        S: Display,
+    {
+        self.git_statuses
+            .get_untracked_color(status, subject, &self.errors)
     }
 
-    /// Get the color for an tracked file's status.
-    pub fn for_tracked_git_status(&self, status: Status) -> Option<Color> {
-        self.git_statuses.get_tracked_color(status)
+    /// Get the color for an tracked file's status. See
+    /// [`Self::for_untracked_git_status`] for `subject`.
+    pub fn for_tracked_git_status<S>(&self, status: Status, subject: S) -> Option<Color>
+    where
+        S: Display,
+    {
+        self.git_statuses
+            .get_tracked_color(status, subject, &self.errors)
     }
 
     /// Gets the color for a file.
@@ -88,26 +130,30 @@ impl FromLua for Colors {
         const GIT_STATUSES_KEY: &str = "git_statuses";
 
         let table = mlua::Table::from_lua(value, lua)?;
-        let for_icon = table.get(FOR_ICON_KEY)?;
+        // NOTE The icon colors can be a function, or a table with a fallback function.
+        let (icon_lookup, for_icon) = Lookup::with_fallback(table.get(FOR_ICON_KEY)?, lua)?;
+        let for_icon = for_icon.into_iter().collect();
         let git_statuses = table
             .get::<Option<GitStatuses>>(GIT_STATUSES_KEY)?
             .unwrap_or_default();
 
         let colors = Self {
+            icon_lookup,
             for_icon,
             git_statuses,
+            errors: ErrorLog::from_lua(lua),
         };
         Ok(colors)
     }
 }
 
 /// The configuration for git status colors.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 struct GitStatuses {
-    /// Function to get the color for tracked statuses.
-    tracked: Option<mlua::Function>,
-    /// Function to get the color for untracked statuses.
-    untracked: Option<mlua::Function>,
+    /// Functions to get the color for tracked statuses.
+    tracked: Vec<mlua::Function>,
+    /// Functions to get the color for untracked statuses.
+    untracked: Vec<mlua::Function>,
 }
 
 impl GitStatuses {
@@ -126,23 +172,57 @@ impl GitStatuses {
     }
 
     /// Gets the color for a tracked git status.
-    fn get_tracked_color(&self, status: Status) -> Option<Color> {
+    fn get_tracked_color<S>(&self, status: Status, subject: S, errors: &ErrorLog) -> Option<Color>
+    where
+        S: Display,
+    {
         let default = Self::get_default_color::<status::Tracked>(status);
-        // TODO Report error
-        self.tracked.as_ref().map_or(default, |f| {
-            f.call::<Option<Color>>((status, default))
-                .unwrap_or(default)
-        })
+        Self::call_chain(
+            &self.tracked,
+            "colors.git_statuses.tracked",
+            status,
+            subject,
+            default,
+            errors,
+        )
     }
 
     /// Gets the color for an untracked git status.
-    fn get_untracked_color(&self, status: Status) -> Option<Color> {
+    fn get_untracked_color<S>(&self, status: Status, subject: S, errors: &ErrorLog) -> Option<Color>
+    where
+        S: Display,
+    {
         let default = Self::get_default_color::<status::Untracked>(status);
-        // TODO Report error
-        self.untracked.as_ref().map_or(default, |f| {
-            f.call::<Option<Color>>((status, default))
-                .unwrap_or(default)
-        })
+        Self::call_chain(
+            &self.untracked,
+            "colors.git_statuses.untracked",
+            status,
+            subject,
+            default,
+            errors,
+        )
+    }
+
+    /// Calls the functions for a status, where each function gets the result of the
+    /// previous function as its default. `subject` is reported if a function raises
+    /// an error.
+    fn call_chain<S>(
+        functions: &[mlua::Function],
+        name: &'static str,
+        status: Status,
+        subject: S,
+        default: Option<Color>,
+        errors: &ErrorLog,
+    ) -> Option<Color>
+    where
+        S: Display,
+    {
+        let mut color = default;
+        for f in functions {
+            let result = f.call::<Option<Color>>((status, color));
+            color = errors.unwrap_or(result, name, f, &subject, color);
+        }
+        color
     }
 }
 
@@ -152,8 +232,14 @@ impl FromLua for GitStatuses {
         const UNTRACKED_KEY: &str = "untracked";
 
         let table = mlua::Table::from_lua(value, lua)?;
-        let tracked = table.get(TRACKED_KEY)?;
-        let untracked = table.get(UNTRACKED_KEY)?;
+        let tracked = table
+            .get::<Option<mlua::Function>>(TRACKED_KEY)?
+            .into_iter()
+            .collect();
+        let untracked = table
+            .get::<Option<mlua::Function>>(UNTRACKED_KEY)?
+            .into_iter()
+            .collect();
 
         let git_statuses = Self { tracked, untracked };
         Ok(git_statuses)
@@ -185,3 +271,30 @@ impl StatusColor for status::Untracked {
     const DEFAULT_REMOVED: AnsiColors = AnsiColors::BrightRed;
     const DEFAULT_RENAMED: AnsiColors = AnsiColors::BrightCyan;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_git_status_error_names_subject() {
+        let lua = Lua::new();
+        let log = ErrorLog::default();
+        log.install(&lua);
+        let colors = lua
+            .load("return { git_statuses = { tracked = function() error('bad') end } }")
+            .eval::<Colors>()
+            .expect("The colors should load");
+
+        let color = colors.for_tracked_git_status(Status::Added, "src/main.rs");
+        // NOTE The default color is used when the callback raises an error.
+        assert!(matches!(color, Some(Color::Ansi(_))));
+
+        let mut report = Vec::new();
+        log.write_report(&mut report)
+            .expect("Writing to a buffer should succeed");
+        let report = String::from_utf8(report).expect("The report should be valid UTF-8");
+        assert!(report.contains("`colors.git_statuses.tracked`"), "{report}");
+        assert!(report.contains(" for src/main.rs: "), "{report}");
+    }
+}
@@ -0,0 +1,245 @@
+//! Module for reporting errors raised by configuration callbacks.
+use mlua::Lua;
+use std::cell::RefCell;
+use std::fmt::{self, Display};
+use std::io::{self, Write};
+use std::rc::Rc;
+
+/// Collects the Lua errors raised by configuration callbacks.
+///
+/// Callbacks fall back to their defaults when they fail, so that one bad callback doesn't
+/// stop the tree from being written. Errors are collected to be reported afterwards, and
+/// are deduplicated by callback and message.
+///
+/// Clones share the same errors. Configurations loaded from a Lua state share the log
+/// set with [`ErrorLog::install`].
+#[derive(Debug, Clone, Default)]
+pub struct ErrorLog {
+    /// The collected errors.
+    errors: Rc<RefCell<Vec<CallbackError>>>,
+    /// Should the first error abort the run?
+    strict: bool,
+}
+
+impl ErrorLog {
+    /// Creates an empty error log. If `strict` is `true`, the first error should abort
+    /// the run.
+    #[inline]
+    pub fn new(strict: bool) -> Self {
+        Self {
+            errors: Rc::default(),
+            strict,
+        }
+    }
+
+    /// Sets the log as the log of configurations loaded from the Lua state.
+    #[inline]
+    pub fn install(&self, lua: &Lua) {
+        lua.set_app_data(self.clone());
+    }
+
+    /// Gets the log of configurations loaded from the Lua state.
+    pub(super) fn from_lua(lua: &Lua) -> Self {
+        lua.app_data_ref::<Self>()
+            .map(|log| log.clone())
+            .unwrap_or_default()
+    }
+
+    /// Should the first error abort the run?
+    #[inline]
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Have any errors been raised?
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.errors.borrow().is_empty()
+    }
+
+    /// Gets the result of a callback, or the default if the callback raised an error.
+    ///
+    /// The error is recorded for the callback, its `function`, and the `subject` the
+    /// callback was called for (like the entry's path).
+    pub(super) fn unwrap_or<T, S>(
+        &self,
+        result: mlua::Result<T>,
+        callback: &'static str,
+        function: &mlua::Function,
+        subject: S,
+        default: T,
+    ) -> T
+    where
+        S: Display,
+    {
+        match result {
+            Ok(value) => value,
+            Err(err) => {
+                self.record(callback, function, subject, &err);
+                default
+            }
+        }
+    }
+
+    /// Records an error raised by a callback.
+    fn record<S>(
+        &self,
+        callback: &'static str,
+        function: &mlua::Function,
+        subject: S,
+        error: &mlua::Error,
+    ) where
+        S: Display,
+    {
+        /// Separates the error message from the Lua traceback.
+        const TRACEBACK_SEPARATOR: &str = "\nstack traceback:\n";
+
+        let error = error.to_string();
+        let (message, traceback) = match error.split_once(TRACEBACK_SEPARATOR) {
+            Some((message, traceback)) => (message, Some(traceback)),
+            None => (error.as_str(), None),
+        };
+
+        let mut errors = self.errors.borrow_mut();
+        // NOTE Callbacks can run again before a strict run is aborted, but only the
+        //      first error aborts it.
+        if self.strict && !errors.is_empty() {
+            return;
+        }
+        let existing = errors
+            .iter_mut()
+            .find(|error| error.callback == callback && error.message == message);
+        match existing {
+            Some(error) => error.count += 1,
+            None => errors.push(CallbackError {
+                callback,
+                location: location(function),
+                message: message.to_string(),
+                traceback: traceback.map(String::from),
+                subject: subject.to_string(),
+                count: 1,
+            }),
+        }
+    }
+
+    /// Gets the number of distinct errors.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.errors.borrow().len()
+    }
+
+    /// Writes a diagnostic for each error.
+    pub fn write_report<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: Write,
+    {
+        for error in self.errors.borrow().iter() {
+            writeln!(writer, "{error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// An error raised by a configuration callback.
+#[derive(Debug)]
+struct CallbackError {
+    /// The name of the callback, like `skip`.
+    callback: &'static str,
+    /// Where the callback is defined, like `config.lua:3`.
+    location: Option<String>,
+    /// The error message, without the traceback.
+    message: String,
+    /// The Lua traceback, if any.
+    traceback: Option<String>,
+    /// What the callback was first called for when it raised the error.
+    subject: String,
+    /// How many times the error was raised.
+    count: usize,
+}
+
+impl Display for CallbackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "error in config callback `{}`", self.callback)?;
+        if let Some(ref location) = self.location {
+            write!(f, " (defined at {location})")?;
+        }
+        write!(f, " for {}", self.subject)?;
+        if self.count > 1 {
+            write!(f, " (and {} more times)", self.count - 1)?;
+        }
+        write!(f, ": {}", self.message)?;
+        if let Some(ref traceback) = self.traceback {
+            write!(f, "\nstack traceback:\n{traceback}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Gets where a Lua function is defined, like `config.lua:3`.
+pub(super) fn location(function: &mlua::Function) -> Option<String> {
+    let info = function.info();
+    // NOTE Sources of files start with `@`. The short source can cut off long paths.
+    let source = match info.source.as_deref().and_then(|s| s.strip_prefix('@')) {
+        Some(path) => path.to_string(),
+        None => info.short_src?,
+    };
+    Some(match info.line_defined {
+        Some(line) => format!("{source}:{line}"),
+        None => source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_log_deduplicates() {
+        let lua = Lua::new();
+        let f = lua
+            .load("return function(path) error('bad path: ' .. path) end")
+            .set_name("@config.lua")
+            .eval::<mlua::Function>()
+            .expect("The chunk should return a function");
+        let log = ErrorLog::default();
+
+        for path in ["a", "a", "b"] {
+            let result = f.call::<bool>(path);
+            assert!(log.unwrap_or(result, "skip", &f, path, true));
+        }
+
+        let mut report = Vec::new();
+        log.write_report(&mut report)
+            .expect("Writing to a buffer should succeed");
+        let report = String::from_utf8(report).expect("The report should be valid UTF-8");
+        assert!(report.starts_with(
+            "error in config callback `skip` (defined at config.lua:1) for a (and 1 more times): runtime error: "
+        ));
+        assert!(report.contains("stack traceback:"));
+        assert!(
+            report.contains("error in config callback `skip` (defined at config.lua:1) for b: ")
+        );
+    }
+
+    #[test]
+    fn test_strict_error_log_keeps_first_error() {
+        let lua = Lua::new();
+        let log = ErrorLog::new(true);
+
+        let f = lua
+            .load("return function(message) error(message) end")
+            .eval::<mlua::Function>()
+            .expect("The chunk should return a function");
+        for message in ["first", "second"] {
+            let result = f.call::<()>(message);
+            log.unwrap_or(result, "skip", &f, "a", ());
+        }
+
+        let mut report = Vec::new();
+        log.write_report(&mut report)
+            .expect("Writing to a buffer should succeed");
+        let report = String::from_utf8(report).expect("The report should be valid UTF-8");
+        assert!(report.contains("first"));
+        assert!(!report.contains("second"));
+    }
+}
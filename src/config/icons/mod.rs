@@ -1,18 +1,35 @@
 //! Module for the icon config.
-use super::ConfigFile;
+use super::lookup::Lookup;
+use super::{ConfigFile, ErrorLog};
 use crate::lua::interop;
 use crate::tree::{
     Entry,
     entry::{Attributes, attributes::FileAttributes},
 };
 use mlua::{FromLua, Lua};
+pub use set::IconSet;
 use std::path::Path;
 
+mod set;
+mod theme;
+
 /// The configuration for icons.
-#[derive(Debug, Default)]
+///
+/// The configuration can be a function, or a table of icons by filename, directory
+/// name, glob, extension, and language, with an optional `fallback` function. The
+/// table is matched before the functions are called.
+///
+/// Like [`super::Main`], configurations can be layered with [`Icons::merge`].
+#[derive(Debug, Default, Clone)]
 pub struct Icons {
-    /// Function to get the icon for an entry.
-    get_icon: Option<mlua::Function>,
+    /// Icons matched before the functions are called.
+    lookup: Lookup<String>,
+    /// Functions to get the icon for an entry.
+    get_icon: Vec<mlua::Function>,
+    /// The set of glyphs used for the default icons.
+    icon_set: IconSet,
+    /// Collects errors raised by the functions.
+    errors: ErrorLog,
 }
 
 impl Icons {
@@ -20,8 +37,6 @@ impl Icons {
     const DEFAULT_FILE_ICON: &'static str = "\u{f0214}"; // 󰈔
     /// The default icon to display when a file is an executable.
     const DEFAULT_EXECUTABLE_ICON: &'static str = "\u{f070e}"; // 󰜎
-    /// The default icon to display for directories/folders.
-    const DEFAULT_DIRECTORY_ICON: &'static str = "\u{f024b}"; // 󰉋
     /// The default icon to display for symlinks.
     const DEFAULT_SYMLINK_ICON: &'static str = "\u{cf481}"; // 
 
@@ -31,38 +46,117 @@ impl Icons {
     /// Get the icon for the entry. If the configuration returns `nil`, a string with
     /// invisible characters will be returned.
     ///
-    /// On a Lua error, this falls back to the default icon choice.
-    pub fn get_icon<P>(&self, entry: &Entry<P>) -> String
+    /// `is_open` is if the entry is a directory with its children shown, which uses an
+    /// open folder icon by default.
+    ///
+    /// Entries that match the icon table use its icon, and the functions are only
+    /// called for the rest. On a Lua error, this falls back to the default icon
+    /// choice. With [`IconSet::None`], this is always empty.
+    pub fn get_icon<P>(&self, entry: &Entry<P>, is_open: bool) -> String
     where
         P: AsRef<Path>,
     {
+        if !self.icon_set.has_icons() {
+            return String::new();
+        }
+        // NOTE Matched entries don't need a Lua function call.
+        if let Some(icon) = self.lookup.get(entry) {
+            return icon.clone();
+        }
         // TODO Use Cow
-        let default_icon = Self::default_icon(entry);
-        self.get_icon
-            .as_ref()
-            .and_then(|f| {
-                let path = entry.path();
-                let attributes = interop::FileAttributes::from(entry);
-                // TODO Report the error when this function fails
-                f.call::<Option<String>>((path, attributes, default_icon))
-                    .ok()
-            })
-            .unwrap_or_else(|| Some(String::from(default_icon)))
-            .unwrap_or_else(|| String::from(Self::EMPTY_ICON))
+        let path = entry.path();
+        let mut icon = Some(String::from(self.default_icon(entry, is_open)));
+        for f in self.get_icon.iter() {
+            let attributes = interop::FileAttributes::from(entry);
+            let result = f.call::<Option<String>>((path, attributes, icon.as_deref()));
+            icon = self
+                .errors
+                .unwrap_or(result, "icons", f, path.display(), icon);
+        }
+        icon.unwrap_or_else(|| String::from(Self::EMPTY_ICON))
+    }
+
+    /// Uses the set of glyphs for the default icons.
+    #[inline]
+    #[must_use]
+    pub fn with_icon_set(self, icon_set: IconSet) -> Self {
+        Self { icon_set, ..self }
+    }
+
+    /// Gets the set of glyphs used for the default icons.
+    #[inline]
+    pub fn icon_set(&self) -> IconSet {
+        self.icon_set
+    }
+
+    /// Merges a configuration on top of this one. The functions of `other` get the
+    /// results of this configuration's functions as their `default`, and the icons
+    /// in the table of `other` take precedence.
+    #[must_use]
+    pub fn merge(mut self, other: Self) -> Self {
+        self.lookup = self.lookup.merge(other.lookup);
+        self.get_icon.extend(other.get_icon);
+        self
+    }
+
+    /// Gets the default icon choice for an entry from the icon set.
+    fn default_icon<P>(&self, entry: &Entry<P>, is_open: bool) -> &'static str
+    where
+        P: AsRef<Path>,
+    {
+        let Some(glyphs) = self.icon_set.glyphs() else {
+            return Self::nerd_icon(entry, is_open);
+        };
+        let folder = if is_open {
+            glyphs.folder_open
+        } else {
+            glyphs.folder
+        };
+        match entry.attributes() {
+            Attributes::Directory(_) => folder,
+            Attributes::File(attributes) if attributes.is_executable() => glyphs.executable,
+            Attributes::File(attributes) => attributes
+                .language()
+                .map(|language| glyphs.language(language))
+                .unwrap_or(glyphs.file),
+            Attributes::Symlink(_) => glyphs.symlink,
+            Attributes::Virtual(attributes) if attributes.is_directory() => folder,
+            Attributes::Virtual(_) => glyphs.file,
+        }
     }
 
-    /// Gets the default icon choice for an entry.
-    fn default_icon<P>(entry: &Entry<P>) -> &str
+    /// Gets the Nerd Font icon for an entry, from the built-in icons of well-known
+    /// files and folders.
+    fn nerd_icon<P>(entry: &Entry<P>, is_open: bool) -> &'static str
     where
         P: AsRef<Path>,
     {
+        let name = entry.path().file_name().and_then(|name| name.to_str());
         match entry.attributes() {
-            Attributes::Directory(_) => Self::DEFAULT_DIRECTORY_ICON,
-            Attributes::File(attributes) => Self::get_file_icon(attributes),
+            Attributes::Directory(_) => Self::get_folder_icon(name, is_open),
+            Attributes::File(attributes) => name
+                .and_then(theme::file_icon)
+                .unwrap_or_else(|| Self::get_file_icon(attributes)),
             Attributes::Symlink(_) => Self::DEFAULT_SYMLINK_ICON,
+            Attributes::Virtual(attributes) if attributes.is_directory() => {
+                Self::get_folder_icon(name, is_open)
+            }
+            Attributes::Virtual(_) => name
+                .and_then(theme::file_icon)
+                .unwrap_or(Self::DEFAULT_FILE_ICON),
         }
     }
 
+    /// Gets the default icon for a directory entry.
+    fn get_folder_icon(name: Option<&str>, is_open: bool) -> &'static str {
+        let default = if is_open {
+            theme::FOLDER_OPEN
+        } else {
+            theme::FOLDER
+        };
+        name.and_then(theme::folder_icon).unwrap_or(default)
+    }
+
     /// Gets the default icon for a file entry.
     fn get_file_icon(attributes: &FileAttributes) -> &'static str {
         if attributes.is_executable() {
@@ -82,6 +176,54 @@ impl ConfigFile for Icons {
 
 impl FromLua for Icons {
     fn from_lua(value: mlua::Value, lua: &Lua) -> mlua::Result<Self> {
-        Option::<mlua::Function>::from_lua(value, lua).map(|get_icon| Self { get_icon })
+        Lookup::with_fallback(value, lua).map(|(lookup, get_icon)| Self {
+            lookup,
+            get_icon: get_icon.into_iter().collect(),
+            icon_set: IconSet::default(),
+            errors: ErrorLog::from_lua(lua),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::entry::Attributes;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_matched_entry_skips_fallback() {
+        const CONFIG: &str = r#"
+            return {
+                filenames = { ["Cargo.toml"] = "cargo" },
+                fallback = function(filename, attributes, default)
+                    calls = (calls or 0) + 1
+                    return "fallback"
+                end,
+            }
+        "#;
+
+        let lua = Lua::new();
+        let icons = lua
+            .load(CONFIG)
+            .eval::<Icons>()
+            .expect("The icons should load");
+        let file = |path: &str| {
+            let attributes =
+                Attributes::new_archived_file(path, Path::new(path), &[][..], 0, false)
+                    .expect("Reading from a slice should succeed");
+            Entry::with_attributes(PathBuf::from(path), attributes)
+        };
+        let calls = || {
+            lua.globals()
+                .get::<Option<i64>>("calls")
+                .expect("The count should be a number")
+                .unwrap_or(0)
+        };
+
+        assert_eq!("cargo", icons.get_icon(&file("./Cargo.toml"), false));
+        assert_eq!(0, calls());
+        assert_eq!("fallback", icons.get_icon(&file("./main.rs"), false));
+        assert_eq!(1, calls());
     }
 }
@@ -0,0 +1,145 @@
+//! Module for the sets of icons to choose from, for terminals without Nerd Fonts.
+use clap::ValueEnum;
+use gengo::Language;
+use gengo::language::Category;
+use mlua::{IntoLua, Lua};
+
+/// The set of glyphs used for the default icons.
+#[derive(Debug, ValueEnum, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IconSet {
+    /// Nerd Font glyphs, with icons for languages and well-known files and folders.
+    #[default]
+    Nerd,
+    /// Emoji, which most terminals can show.
+    Emoji,
+    /// Unicode symbols, which most fonts have.
+    Unicode,
+    /// Only ASCII characters, like for CI logs.
+    Ascii,
+    /// No icons. The icon column is removed.
+    None,
+}
+
+impl IconSet {
+    /// Gets the name of the set, like `nerd`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Nerd => "nerd",
+            Self::Emoji => "emoji",
+            Self::Unicode => "unicode",
+            Self::Ascii => "ascii",
+            Self::None => "none",
+        }
+    }
+
+    /// Does the set show icons?
+    #[inline]
+    pub const fn has_icons(self) -> bool {
+        !matches!(self, Self::None)
+    }
+
+    /// Gets the glyphs of the set. Nerd Font glyphs come from the built-in theme and
+    /// languages instead.
+    pub(super) const fn glyphs(self) -> Option<&'static Glyphs> {
+        match self {
+            Self::Nerd | Self::None => None,
+            Self::Emoji => Some(&Glyphs::EMOJI),
+            Self::Unicode => Some(&Glyphs::UNICODE),
+            Self::Ascii => Some(&Glyphs::ASCII),
+        }
+    }
+}
+
+impl IntoLua for IconSet {
+    #[inline]
+    fn into_lua(self, lua: &Lua) -> mlua::Result<mlua::Value> {
+        self.name().into_lua(lua)
+    }
+}
+
+/// The glyphs of an icon set.
+pub(super) struct Glyphs {
+    /// A directory with its children hidden.
+    pub(super) folder: &'static str,
+    /// A directory with its children shown.
+    pub(super) folder_open: &'static str,
+    /// A file without a detected language.
+    pub(super) file: &'static str,
+    /// An executable file.
+    pub(super) executable: &'static str,
+    /// A symlink.
+    pub(super) symlink: &'static str,
+    /// A programming language, like Rust.
+    programming: &'static str,
+    /// A data language, like JSON.
+    data: &'static str,
+    /// A markup language, like HTML.
+    markup: &'static str,
+    /// Prose, like Markdown.
+    prose: &'static str,
+    /// A query language, like SQL.
+    query: &'static str,
+    /// A pattern language, like Regex.
+    pattern: &'static str,
+}
+
+impl Glyphs {
+    /// Emoji glyphs.
+    const EMOJI: Self = Self {
+        folder: "📁",
+        folder_open: "📂",
+        file: "📄",
+        executable: "🚀",
+        symlink: "🔗",
+        programming: "📜",
+        data: "📊",
+        markup: "🌐",
+        prose: "📝",
+        query: "🔍",
+        pattern: "🧩",
+    };
+
+    /// Unicode symbol glyphs.
+    const UNICODE: Self = Self {
+        folder: "▸",
+        folder_open: "▾",
+        file: "•",
+        executable: "★",
+        symlink: "→",
+        programming: "λ",
+        data: "≡",
+        markup: "◇",
+        prose: "¶",
+        query: "§",
+        pattern: "※",
+    };
+
+    /// ASCII glyphs.
+    const ASCII: Self = Self {
+        folder: "+",
+        folder_open: "-",
+        file: ".",
+        executable: "*",
+        symlink: "@",
+        programming: "#",
+        data: "=",
+        markup: "<",
+        prose: "~",
+        query: "?",
+        pattern: "%",
+    };
+
+    /// Gets the glyph for a language by its category.
+    pub(super) fn language(&self, language: Language) -> &'static str {
+        match language.category() {
+            Category::Programming => self.programming,
+            Category::Data => self.data,
+            Category::Markup => self.markup,
+            Category::Prose => self.prose,
+            Category::Query => self.query,
+            Category::Pattern => self.pattern,
+            // NOTE Categories can be added by gengo.
+            _ => self.file,
+        }
+    }
+}
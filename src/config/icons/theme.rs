@@ -0,0 +1,119 @@
+//! Module for the built-in Nerd Font icons of well-known files and folders.
+
+/// The icon of a closed folder.
+pub(super) const FOLDER: &str = "\u{f024b}"; // 󰉋
+/// The icon of an open folder, whose children are shown.
+pub(super) const FOLDER_OPEN: &str = "\u{f0770}"; // 󰝰
+
+/// Gets the icon for a well-known filename, like `Cargo.toml` or `LICENSE`.
+pub(super) fn file_icon(name: &str) -> Option<&'static str> {
+    let icon = match name {
+        // 
+        "Cargo.toml" | "Cargo.lock" | "rust-toolchain" | "rust-toolchain.toml" => "\u{e7a8}",
+        // 
+        "Dockerfile"
+        | "Containerfile"
+        | ".dockerignore"
+        | "docker-compose.yml"
+        | "docker-compose.yaml"
+        | "compose.yml"
+        | "compose.yaml" => "\u{f308}",
+        // 
+        "Makefile" | "GNUmakefile" | "makefile" | "CMakeLists.txt" | "justfile" | "Justfile" => {
+            "\u{e673}"
+        }
+        // 
+        ".gitignore" | ".gitattributes" | ".gitmodules" | ".gitkeep" | ".mailmap" => "\u{e702}",
+        // 
+        "package.json" | "package-lock.json" | ".npmrc" | ".npmignore" => "\u{e71e}",
+        // 
+        "yarn.lock" | ".yarnrc" | ".yarnrc.yml" => "\u{e6a7}",
+        // 
+        "tsconfig.json" => "\u{e628}",
+        // 
+        "go.mod" | "go.sum" | "go.work" => "\u{e627}",
+        // 
+        "Gemfile" | "Gemfile.lock" | "Rakefile" | ".ruby-version" => "\u{e739}",
+        // 
+        "pyproject.toml" | "requirements.txt" | "setup.py" | "Pipfile" | "Pipfile.lock"
+        | "poetry.lock" | "uv.lock" => "\u{e73c}",
+        // 
+        "flake.nix" | "flake.lock" | "default.nix" | "shell.nix" => "\u{f313}",
+        // 
+        ".editorconfig" => "\u{e652}",
+        // 
+        ".env" | ".envrc" => "\u{f084}",
+        _ => return prefixed_file_icon(name),
+    };
+    Some(icon)
+}
+
+/// Gets the icon for files that are recognized by the start of their name, like
+/// `LICENSE-MIT` and `README.md`.
+fn prefixed_file_icon(name: &str) -> Option<&'static str> {
+    /// Icons by case-insensitive prefix.
+    const PREFIXES: &[(&str, &str)] = &[
+        ("LICENSE", "\u{f24e}"),   // 
+        ("LICENCE", "\u{f24e}"),   // 
+        ("COPYING", "\u{f24e}"),   // 
+        ("README", "\u{f405}"),    // 
+        ("CHANGELOG", "\u{f1da}"), // 
+        ("CHANGES", "\u{f1da}"),   // 
+    ];
+    PREFIXES
+        .iter()
+        .find(|(prefix, _)| {
+            name.get(..prefix.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+        })
+        .map(|(_, icon)| *icon)
+}
+
+/// Gets the icon for a well-known folder name, like `.github` or `node_modules`.
+/// Well-known folders use the same icon when they're open and closed.
+pub(super) fn folder_icon(name: &str) -> Option<&'static str> {
+    let icon = match name {
+        // 
+        ".git" => "\u{e5fb}",
+        // 
+        ".github" => "\u{e5fd}",
+        // 
+        ".config" => "\u{e5fc}",
+        // 
+        "node_modules" => "\u{e5fa}",
+        // 
+        ".vscode" => "\u{e70c}",
+        // 
+        ".cargo" => "\u{e7a8}",
+        // 
+        "src" | "source" | "lib" => "\u{f44f}",
+        // 󰙨
+        "tests" | "test" | "spec" | "__tests__" | "benches" => "\u{f0668}",
+        // 
+        "docs" | "doc" => "\u{f405}",
+        // 
+        "target" | "build" | "dist" | "out" => "\u{f487}",
+        // 
+        "scripts" | "bin" => "\u{f489}",
+        // 󰉏
+        "assets" | "images" | "img" => "\u{f024f}",
+        _ => return None,
+    };
+    Some(icon)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("Cargo.toml", Some("\u{e7a8}"))]
+    #[case("LICENSE-MIT", Some("\u{f24e}"))]
+    #[case("license.txt", Some("\u{f24e}"))]
+    #[case("README.md", Some("\u{f405}"))]
+    #[case("main.rs", None)]
+    fn test_file_icon(#[case] name: &str, #[case] expected: Option<&str>) {
+        assert_eq!(expected, file_icon(name));
+    }
+}
@@ -0,0 +1,87 @@
+-- The unified configuration. While this file exists, `config.lua`, `icons.lua`, and
+-- `colors.lua` are ignored.
+return {
+  ---@type "auto"|"on"|"ansi"|"off"|nil
+  color = "auto",
+  -- Either the name of a built-in charset, or a table with custom text. All the texts
+  -- must have the same display width.
+  ---@type "ascii"|"standard"|"rounded"|"heavy"|"double"|"empty"|Charset|nil
+  charset = "standard",
+  ---@param filepath string Path to the file relative to the starting directory
+  ---@param attributes FileAttributes
+  ---@param default boolean
+  ---@return boolean
+  skip = function(filepath, attributes, default)
+    -- The default is to hide dotfiles on Unix and files with the hidden attribute on
+    -- Windows.
+    return default
+  end,
+  ---@param dirpath string Path to the directory relative to the starting directory
+  ---@param attributes FileAttributes
+  ---@param default integer|nil The limit from `--filelimit`
+  ---@return integer|nil
+  filelimit = function(dirpath, attributes, default)
+    return default
+  end,
+  ---@param dirpath string Path to the directory relative to the starting directory
+  ---@param attributes FileAttributes
+  ---@param default integer|nil The limit from `--max-entries`
+  ---@return integer|nil
+  max_entries = function(dirpath, attributes, default)
+    return default
+  end,
+  ---@param filepath string Path to the file relative to the starting directory
+  ---@param attributes FileAttributes
+  ---@param default string|nil The `file://` URL of the file's absolute path
+  ---@return string|nil
+  hyperlink = function(filepath, attributes, default)
+    return default
+  end,
+  -- Entries in a directory are sorted by the keys this returns, which can be strings
+  -- or numbers (numbers come first). The default key is the file name. Without this
+  -- function, entries are sorted by name without reading the ones that aren't shown.
+  -- For example, this lists directories first:
+  --
+  -- ---@param filepath string Path to the file relative to the starting directory
+  -- ---@param attributes FileAttributes
+  -- ---@param default string The file name
+  -- ---@return string|number
+  -- sort = function(filepath, attributes, default)
+  --   local prefix = attributes.file_type == "directory" and "0" or "1"
+  --   return prefix .. default
+  -- end,
+  ---@param summary Summary The totals of the displayed tree
+  ---@param default string The default report, like "1 directory, 2 files"
+  ---@return string|nil
+  on_finish = function(summary, default)
+    return default
+  end,
+  ---@param filepath string Path to the file relative to the starting directory
+  ---@param attributes FileAttributes
+  ---@param default string The default icon
+  ---@return string|nil
+  icons = function(filepath, attributes, default)
+    return default
+  end,
+  ---@param filepath string
+  ---@param attributes FileAttributes
+  ---@param default Color|nil
+  ---@return Color|nil
+  colors = function(filepath, attributes, default)
+    return default
+  end,
+  git_statuses = {
+    ---@param status GitStatus
+    ---@param default Color|nil
+    ---@return Color|nil
+    untracked = function(status, default)
+      return default
+    end,
+    ---@param status GitStatus
+    ---@param default Color|nil
+    ---@return Color|nil
+    tracked = function(status, default)
+      return default
+    end,
+  },
+}
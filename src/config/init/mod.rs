@@ -0,0 +1,147 @@
+//! Module for the unified configuration file.
+use super::{Colors, ConfigDir, ConfigFile, Configs, Icons, Main};
+use mlua::{FromLua, Lua};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+impl ConfigFile for Configs {
+    const FILENAME: &'static str = "init.lua";
+    const DEFAULT_MODULE: &'static str = include_str!("./init.lua");
+}
+
+/// The unified configuration is a table with the keys of [`Main`], the icon function
+/// as `icons`, the icon color function as `colors`, and the `git_statuses` table of
+/// [`Colors`].
+impl FromLua for Configs {
+    fn from_lua(value: mlua::Value, lua: &Lua) -> mlua::Result<Self> {
+        let table = mlua::Table::from_lua(value, lua)?;
+        let main = Main::from_lua(mlua::Value::Table(table.clone()), lua)?;
+        let icons = Icons::from_lua(table.get("icons")?, lua)?;
+
+        // NOTE The unified keys are renamed to the keys of `colors.lua`.
+        let colors = lua.create_table()?;
+        colors.set("icons", table.get::<mlua::Value>("colors")?)?;
+        colors.set("git_statuses", table.get::<mlua::Value>("git_statuses")?)?;
+        let colors = Colors::from_lua(mlua::Value::Table(colors), lua)?;
+
+        let configs = Self {
+            main: Some(main),
+            icons: Some(icons),
+            colors: Some(colors),
+        };
+        Ok(configs)
+    }
+}
+
+impl ConfigDir {
+    /// Gets the path to the unified configuration file.
+    #[inline]
+    pub fn init_path(&self) -> PathBuf {
+        self.file_name::<Configs>()
+    }
+
+    /// Generates a unified configuration file from the separate configuration files.
+    /// Returns `None` if there are no separate files to migrate.
+    ///
+    /// The generated file keeps the code of each file, so that the behavior doesn't
+    /// change.
+    pub fn migrate_to_init(&self) -> io::Result<Option<String>> {
+        let mut contents = String::from(
+            "-- Generated by `fancy-tree --edit-config init` from the separate configuration\n\
+             -- files, which are ignored while this file exists.\n",
+        );
+        let mut migrated = false;
+        for (name, path) in [
+            ("config", self.main_path()),
+            ("icons", self.icons_path()),
+            ("colors", self.colors_path()),
+        ] {
+            let source = match fs::read_to_string(&path) {
+                Ok(source) => source,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                    contents.push_str(&format!("\nlocal {name} = nil\n"));
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            migrated = true;
+            // NOTE The source isn't indented, because that would change multi-line
+            //      strings. A newline is added in case the file ends with a comment.
+            contents.push_str(&format!(
+                "\n-- From {}\nlocal {name} = (function()\n{}\nend)()\n",
+                path.file_name().unwrap_or_default().display(),
+                source.trim_end()
+            ));
+        }
+        if !migrated {
+            return Ok(None);
+        }
+        contents.push_str(
+            r#"
+local init = {}
+for key, value in pairs(config or {}) do
+  init[key] = value
+end
+init.icons = icons
+if colors then
+  init.colors = colors.icons
+  init.git_statuses = colors.git_statuses
+end
+return init
+"#,
+        );
+        Ok(Some(contents))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrated_init_keeps_behavior() {
+        let dir = tempfile::tempdir().expect("A temporary directory should be created");
+        fs::write(
+            dir.path().join(Main::FILENAME),
+            "return { color = 'off' } -- trailing comment",
+        )
+        .expect("Fixture files should be created");
+        fs::write(
+            dir.path().join(Colors::FILENAME),
+            "return { icons = function() return 'red' end }",
+        )
+        .expect("Fixture files should be created");
+        let config_dir = ConfigDir::from_path(dir.path());
+
+        let contents = config_dir
+            .migrate_to_init()
+            .expect("The files should be readable")
+            .expect("There are files to migrate");
+        fs::write(config_dir.init_path(), contents).expect("The file should be written");
+
+        let lua = Lua::new();
+        let configs = config_dir
+            .load(&lua)
+            .expect("The unified config should load");
+        let main = configs.main.expect("The main config should exist");
+        assert!(matches!(
+            main.color_choice(),
+            crate::color::ColorChoice::Off
+        ));
+        assert!(configs.icons.is_some());
+        assert!(configs.colors.is_some());
+    }
+
+    #[test]
+    fn test_migrate_without_files() {
+        let dir = tempfile::tempdir().expect("A temporary directory should be created");
+        let config_dir = ConfigDir::from_path(dir.path());
+        assert_eq!(
+            None,
+            config_dir
+                .migrate_to_init()
+                .expect("The directory should be readable")
+        );
+    }
+}
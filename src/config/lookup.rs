@@ -0,0 +1,254 @@
+//! Module for declarative tables that map entries to values, like icons.
+use crate::tree::Entry;
+use crate::tree::entry::Attributes;
+use mlua::{ErrorContext as _, FromLua, Lua};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Values for entries by exact filename, directory name, glob, extension, and
+/// language name. This is compiled once from a Lua table, so that the common cases
+/// don't need a Lua function call for each entry.
+#[derive(Debug, Clone)]
+pub(super) struct Lookup<T> {
+    /// Values for files by exact filename.
+    filenames: HashMap<String, T>,
+    /// Values for directories by exact name.
+    directories: HashMap<String, T>,
+    /// Values for entries with names that match the glob. Longer patterns come first.
+    globs: Vec<(glob::Pattern, T)>,
+    /// Values for files by extension, like `rs` or `tar.gz`.
+    extensions: HashMap<String, T>,
+    /// Values for files by language name, like `Rust`.
+    languages: HashMap<String, T>,
+}
+
+impl<T> Lookup<T> {
+    /// The key of the function that's called for entries the table doesn't match.
+    pub(super) const FALLBACK_KEY: &str = "fallback";
+
+    /// Gets the value for the entry. Exact names take priority over globs, globs over
+    /// extensions, and extensions over languages.
+    pub(super) fn get<P>(&self, entry: &Entry<P>) -> Option<&T>
+    where
+        P: AsRef<Path>,
+    {
+        let name = entry.path().file_name()?.to_str()?;
+        let attributes = entry.attributes();
+        if attributes.is_directory() {
+            return self
+                .directories
+                .get(name)
+                .or_else(|| self.get_by_glob(name));
+        }
+
+        self.filenames
+            .get(name)
+            .or_else(|| self.get_by_glob(name))
+            .or_else(|| self.get_by_extension(name))
+            .or_else(|| self.get_by_language(attributes))
+    }
+
+    /// Merges a table on top of this one. The values of `other` take precedence.
+    #[must_use]
+    pub(super) fn merge(mut self, other: Self) -> Self {
+        self.filenames.extend(other.filenames);
+        self.directories.extend(other.directories);
+        self.extensions.extend(other.extensions);
+        self.languages.extend(other.languages);
+        self.globs.retain(|(pattern, _)| {
+            !other
+                .globs
+                .iter()
+                .any(|(other_pattern, _)| other_pattern == pattern)
+        });
+        self.globs.extend(other.globs);
+        sort_globs(&mut self.globs);
+        self
+    }
+
+    /// Gets the value of the first glob that matches the name.
+    fn get_by_glob(&self, name: &str) -> Option<&T> {
+        self.globs
+            .iter()
+            .find(|(pattern, _)| pattern.matches(name))
+            .map(|(_, value)| value)
+    }
+
+    /// Gets the value for the longest extension of the name, so `tar.gz` is tried
+    /// before `gz`.
+    fn get_by_extension(&self, name: &str) -> Option<&T> {
+        if self.extensions.is_empty() {
+            return None;
+        }
+        name.char_indices()
+            // NOTE A leading dot is part of the name, like `.gitignore`.
+            .filter(|(index, c)| *index > 0 && *c == '.')
+            .find_map(|(index, _)| self.extensions.get(&name[index + 1..]))
+    }
+
+    /// Gets the value for the file's language.
+    fn get_by_language(&self, attributes: &Attributes) -> Option<&T> {
+        let language = attributes.file()?.language()?;
+        self.languages.get(language.name())
+    }
+}
+
+impl<T> Lookup<T>
+where
+    T: FromLua,
+{
+    /// Compiles the lookup table from a Lua table with optional `filenames`,
+    /// `directories`, `globs`, `extensions`, and `languages` tables.
+    pub(super) fn from_table(table: &mlua::Table) -> mlua::Result<Self> {
+        /// Gets the table at the key as a map.
+        fn map<T>(table: &mlua::Table, key: &str) -> mlua::Result<HashMap<String, T>>
+        where
+            T: FromLua,
+        {
+            let Some(map) = table.get::<Option<mlua::Table>>(key)? else {
+                return Ok(HashMap::new());
+            };
+            map.pairs::<String, T>()
+                .collect::<mlua::Result<HashMap<_, _>>>()
+                .with_context(|_| format!("invalid `{key}` table"))
+        }
+
+        let extensions = map::<T>(table, "extensions")?
+            .into_iter()
+            .map(|(extension, value)| match extension.strip_prefix('.') {
+                Some(extension) => (String::from(extension), value),
+                None => (extension, value),
+            })
+            .collect();
+        let mut globs = map::<T>(table, "globs")?
+            .into_iter()
+            .map(|(pattern, value)| {
+                glob::Pattern::new(&pattern)
+                    .map(|pattern| (pattern, value))
+                    .map_err(|err| mlua::Error::runtime(format!("invalid glob `{pattern}`: {err}")))
+            })
+            .collect::<mlua::Result<Vec<_>>>()?;
+        sort_globs(&mut globs);
+
+        let lookup = Self {
+            filenames: map(table, "filenames")?,
+            directories: map(table, "directories")?,
+            globs,
+            extensions,
+            languages: map(table, "languages")?,
+        };
+        Ok(lookup)
+    }
+
+    /// Creates the lookup table and the function from a value that's either a
+    /// function, or a lookup table with an optional `fallback` function.
+    pub(super) fn with_fallback(
+        value: mlua::Value,
+        lua: &Lua,
+    ) -> mlua::Result<(Self, Option<mlua::Function>)> {
+        match value {
+            mlua::Value::Table(table) => {
+                let lookup = Self::from_table(&table)?;
+                let fallback = table.get::<Option<mlua::Function>>(Self::FALLBACK_KEY)?;
+                Ok((lookup, fallback))
+            }
+            value => {
+                let function = Option::<mlua::Function>::from_lua(value, lua)?;
+                Ok((Self::default(), function))
+            }
+        }
+    }
+}
+
+impl<T> Default for Lookup<T> {
+    fn default() -> Self {
+        Self {
+            filenames: HashMap::new(),
+            directories: HashMap::new(),
+            globs: Vec::new(),
+            extensions: HashMap::new(),
+            languages: HashMap::new(),
+        }
+    }
+}
+
+/// Sorts globs so that longer, more specific, patterns are matched first.
+fn sort_globs<T>(globs: &mut [(glob::Pattern, T)]) {
+    globs.sort_by(|(a, _), (b, _)| {
+        let (a, b) = (a.as_str(), b.as_str());
+        b.len().cmp(&a.len()).then_with(|| a.cmp(b))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::entry::Attributes;
+    use std::path::PathBuf;
+
+    /// Creates an archived file entry, with a detected language.
+    fn file(path: &str, contents: &str) -> Entry<PathBuf> {
+        let path = PathBuf::from(path);
+        let attributes = Attributes::new_archived_file(
+            &path,
+            &path,
+            contents.as_bytes(),
+            contents.len() as u64,
+            false,
+        )
+        .expect("Reading from a slice should succeed");
+        Entry::with_attributes(path, attributes)
+    }
+
+    #[test]
+    fn test_lookup_priority() {
+        let lua = Lua::new();
+        let table = lua
+            .load(
+                r#"
+                return {
+                    filenames = { ["Cargo.toml"] = "cargo" },
+                    directories = { src = "source" },
+                    globs = { ["*.min.*"] = "minified", ["*.js"] = "glob" },
+                    extensions = { js = "javascript", [".gz"] = "gzip", ["tar.gz"] = "tarball" },
+                    languages = { Rust = "rust" },
+                }
+                "#,
+            )
+            .eval::<mlua::Table>()
+            .expect("The table should be created");
+        let lookup = Lookup::<String>::from_table(&table).expect("The table should compile");
+
+        let get = |entry: &Entry<PathBuf>| lookup.get(entry).map(String::as_str);
+        assert_eq!(Some("cargo"), get(&file("./Cargo.toml", "")));
+        assert_eq!(Some("minified"), get(&file("./app.min.js", "")));
+        assert_eq!(Some("glob"), get(&file("./app.js", "")));
+        assert_eq!(Some("tarball"), get(&file("./release.tar.gz", "")));
+        assert_eq!(Some("gzip"), get(&file("./log.gz", "")));
+        assert_eq!(Some("rust"), get(&file("./main.rs", "fn main() {}\n")));
+        assert_eq!(None, get(&file("./README", "")));
+
+        let src =
+            Entry::with_attributes(PathBuf::from("./src"), Attributes::new_archived_directory());
+        assert_eq!(Some("source"), get(&src));
+    }
+
+    #[test]
+    fn test_merge_overrides_values() {
+        let lua = Lua::new();
+        let compile = |source: &str| {
+            let table = lua
+                .load(source)
+                .eval::<mlua::Table>()
+                .expect("The table should be created");
+            Lookup::<String>::from_table(&table).expect("The table should compile")
+        };
+        let base = compile("return { filenames = { a = 'base', b = 'base' } }");
+        let other = compile("return { filenames = { b = 'other' } }");
+        let lookup = base.merge(other);
+
+        let get = |path: &str| lookup.get(&file(path, "")).cloned();
+        assert_eq!(Some(String::from("base")), get("./a"));
+        assert_eq!(Some(String::from("other")), get("./b"));
+    }
+}
@@ -1,6 +1,10 @@
 return {
   ---@type "auto"|"on"|"ansi"|"off"|nil
   color = "auto",
+  -- Either the name of a built-in charset, or a table with custom text. All the texts
+  -- must have the same display width.
+  ---@type "ascii"|"standard"|"rounded"|"heavy"|"double"|"empty"|Charset|nil
+  charset = "standard",
   ---@param filepath string Path to the file relative to the starting directory
   ---@param attributes FileAttributes
   ---@param default boolean
@@ -10,4 +14,57 @@ return {
     -- Windows.
     return default
   end,
+  ---@param dirpath string Path to the directory relative to the starting directory
+  ---@param attributes FileAttributes
+  ---@param default integer|nil The limit from `--filelimit`
+  ---@return integer|nil
+  filelimit = function(dirpath, attributes, default)
+    -- Directories with more entries than this are not descended into. Return nil for
+    -- no limit.
+    return default
+  end,
+  ---@param dirpath string Path to the directory relative to the starting directory
+  ---@param attributes FileAttributes
+  ---@param default integer|nil The limit from `--max-entries`
+  ---@return integer|nil
+  max_entries = function(dirpath, attributes, default)
+    -- At most this many entries are shown for the directory. Return nil for no limit.
+    return default
+  end,
+  ---@param filepath string Path to the file relative to the starting directory
+  ---@param attributes FileAttributes
+  ---@param default string|nil The `file://` URL of the file's absolute path
+  ---@return string|nil
+  hyperlink = function(filepath, attributes, default)
+    -- The file name links to this URL when hyperlinks are enabled. Return nil to not
+    -- link the file. For example, this links to the file at the current commit:
+    --
+    -- local git = fancytree.git
+    -- local head = git and git.head()
+    -- local path = git and git.relative_path(filepath)
+    -- if head and path then
+    --   return "https://example.com/owner/repo/blob/" .. head .. "/" .. path
+    -- end
+    return default
+  end,
+  -- Entries in a directory are sorted by the keys this returns, which can be strings
+  -- or numbers (numbers come first). The default key is the file name. Without this
+  -- function, entries are sorted by name without reading the ones that aren't shown.
+  -- For example, this lists directories first:
+  --
+  -- ---@param filepath string Path to the file relative to the starting directory
+  -- ---@param attributes FileAttributes
+  -- ---@param default string The file name
+  -- ---@return string|number
+  -- sort = function(filepath, attributes, default)
+  --   local prefix = attributes.file_type == "directory" and "0" or "1"
+  --   return prefix .. default
+  -- end,
+  ---@param summary Summary The totals of the displayed tree
+  ---@param default string The default report, like "1 directory, 2 files"
+  ---@return string|nil
+  on_finish = function(summary, default)
+    -- Return nil to hide the report.
+    return default
+  end,
 }
@@ -1,44 +1,209 @@
 //! Module for the main config.
-use super::ConfigFile;
+use super::{ConfigFile, ErrorLog};
 use crate::color::ColorChoice;
 use crate::lua::interop;
-use crate::tree::Entry;
-use mlua::{FromLua, Lua};
+use crate::tree::{Charset, Entry, Summary};
+use mlua::{FromLua, IntoLua, Lua};
+use std::cmp::Ordering;
 use std::path::Path;
 
 /// The main configuration type.
-#[derive(Debug, Default)]
+///
+/// Configurations can be layered with [`Main::merge`], so each function is a chain of
+/// functions, where each function gets the result of the previous one as its
+/// `default`.
+#[derive(Debug, Default, Clone)]
 pub struct Main {
     /// Determines when/how the application should show colors.
-    color: ColorChoice,
-    /// Function to determine if a file should be skipped.
-    skip: Option<mlua::Function>,
+    color: Option<ColorChoice>,
+    /// The charset used to draw the tree.
+    charset: Option<Charset<'static>>,
+    /// Functions to determine if a file should be skipped.
+    skip: Vec<mlua::Function>,
+    /// Functions to format the summary report after the tree.
+    on_finish: Vec<mlua::Function>,
+    /// Functions to get the filelimit for a directory.
+    filelimit: Vec<mlua::Function>,
+    /// Functions to get the maximum number of entries to show for a directory.
+    max_entries: Vec<mlua::Function>,
+    /// Functions to get the URL to link an entry to.
+    hyperlink: Vec<mlua::Function>,
+    /// Functions to get the key that entries are sorted by.
+    sort: Vec<mlua::Function>,
+    /// Collects errors raised by the functions.
+    errors: ErrorLog,
 }
 
 impl Main {
     /// Gets the configured color choice.
     #[inline]
     pub fn color_choice(&self) -> ColorChoice {
-        self.color
+        self.color.unwrap_or_default()
     }
+
+    /// Gets the configured charset.
+    #[inline]
+    pub fn charset(&self) -> Option<&Charset<'static>> {
+        self.charset.as_ref()
+    }
+
+    /// Merges a configuration on top of this one, like a project's configuration on
+    /// top of the user's.
+    ///
+    /// Values set by `other` take precedence, and the functions of `other` get the
+    /// results of this configuration's functions as their `default`.
+    #[must_use]
+    pub fn merge(mut self, other: Self) -> Self {
+        self.color = other.color.or(self.color);
+        self.charset = other.charset.or(self.charset);
+        self.skip.extend(other.skip);
+        self.on_finish.extend(other.on_finish);
+        self.filelimit.extend(other.filelimit);
+        self.max_entries.extend(other.max_entries);
+        self.hyperlink.extend(other.hyperlink);
+        self.sort.extend(other.sort);
+        self
+    }
+
     /// Should a file be skipped according to the configuration?
     ///
-    /// `git_helper` is used to provide interoperability with git, which this config
-    /// type isn't aware of.
-    pub fn should_skip<P, F>(&self, entry: &Entry<P>, git_helper: F) -> bool
+    /// `default_helper` decides the default that's passed to the `skip` functions,
+    /// like if the entry is hidden or git-ignored, which this config type isn't aware
+    /// of.
+    pub fn should_skip<P, F>(&self, entry: &Entry<P>, default_helper: F) -> bool
     where
         P: AsRef<Path>,
         F: FnOnce() -> bool,
     {
-        let default = entry.is_hidden() || git_helper();
         let path = entry.path();
-        let attributes = interop::FileAttributes::from(entry);
+        let mut skip = default_helper();
+        for f in self.skip.iter() {
+            let attributes = interop::FileAttributes::from(entry);
+            let result = f.call::<bool>((path, attributes, skip));
+            skip = self
+                .errors
+                .unwrap_or(result, "config.skip", f, path.display(), skip);
+        }
+        skip
+    }
+
+    /// Gets the maximum number of entries a directory can have to be descended into.
+    /// `default` is used if the config doesn't have a `filelimit` function.
+    ///
+    /// On a Lua error, this falls back to the default.
+    #[inline]
+    pub fn filelimit<P>(&self, entry: &Entry<P>, default: Option<usize>) -> Option<usize>
+    where
+        P: AsRef<Path>,
+    {
+        self.call_limit("config.filelimit", &self.filelimit, entry, default)
+    }
+
+    /// Gets the maximum number of entries to display for a directory. `default` is
+    /// used if the config doesn't have a `max_entries` function.
+    ///
+    /// On a Lua error, this falls back to the default.
+    #[inline]
+    pub fn max_entries<P>(&self, entry: &Entry<P>, default: Option<usize>) -> Option<usize>
+    where
+        P: AsRef<Path>,
+    {
+        self.call_limit("config.max_entries", &self.max_entries, entry, default)
+    }
+
+    /// Calls functions that return a limit for a directory.
+    fn call_limit<P>(
+        &self,
+        name: &'static str,
+        functions: &[mlua::Function],
+        entry: &Entry<P>,
+        default: Option<usize>,
+    ) -> Option<usize>
+    where
+        P: AsRef<Path>,
+    {
+        let path = entry.path();
+        let mut limit = default;
+        for f in functions {
+            let attributes = interop::FileAttributes::from(entry);
+            let result = f.call::<Option<usize>>((path, attributes, limit));
+            limit = self
+                .errors
+                .unwrap_or(result, name, f, path.display(), limit);
+        }
+        limit
+    }
+
+    /// Gets the URL to link the entry to, if any. `default` is the `file://` URL of
+    /// the entry's absolute path, if it exists.
+    ///
+    /// On a Lua error, this falls back to the default.
+    pub fn hyperlink<P>(&self, entry: &Entry<P>, default: Option<String>) -> Option<String>
+    where
+        P: AsRef<Path>,
+    {
+        let path = entry.path();
+        let mut url = default;
+        for f in self.hyperlink.iter() {
+            let attributes = interop::FileAttributes::from(entry);
+            let result = f.call::<Option<String>>((path, attributes, url.as_deref()));
+            url = self
+                .errors
+                .unwrap_or(result, "config.hyperlink", f, path.display(), url);
+        }
+        url
+    }
+
+    /// Does the configuration change which entries are skipped?
+    #[inline]
+    pub fn has_skip(&self) -> bool {
+        !self.skip.is_empty()
+    }
+
+    /// Does the configuration change how entries are sorted?
+    #[inline]
+    pub fn has_sort(&self) -> bool {
+        !self.sort.is_empty()
+    }
+
+    /// Gets the key to sort the entry by among its siblings. The default key is the
+    /// entry's file name.
+    ///
+    /// On a Lua error, this falls back to the default.
+    pub fn sort_key<P>(&self, entry: &Entry<P>) -> SortKey
+    where
+        P: AsRef<Path>,
+    {
+        let path = entry.path();
+        let name = path
+            .file_name()
+            .unwrap_or(path.as_os_str())
+            .to_string_lossy();
+        let mut key = SortKey::Text(name.into_owned());
+        for f in self.sort.iter() {
+            let attributes = interop::FileAttributes::from(entry);
+            let result = f.call::<SortKey>((path, attributes, &key));
+            key = self
+                .errors
+                .unwrap_or(result, "config.sort", f, path.display(), key);
+        }
+        key
+    }
 
-        // TODO Report error
-        self.skip
-            .as_ref()
-            .map_or(Ok(default), |f| f.call::<bool>((path, attributes, default)))
-            .unwrap_or(default)
+    /// Formats the summary report according to the configuration. Returns `None` if
+    /// the report should not be shown.
+    ///
+    /// On a Lua error, this falls back to the default report.
+    pub fn format_summary(&self, summary: &Summary) -> Option<String> {
+        let mut report = Some(summary.to_string());
+        for f in self.on_finish.iter() {
+            let lua_summary = interop::Summary::from(summary);
+            let result = f.call::<Option<String>>((lua_summary, report.as_deref()));
+            report = self
+                .errors
+                .unwrap_or(result, "config.on_finish", f, "the summary", report);
+        }
+        report
     }
 }
 
@@ -48,7 +213,7 @@ impl ConfigFile for Main {
 }
 
 impl FromLua for Main {
-    fn from_lua(value: mlua::Value, _lua: &Lua) -> mlua::Result<Self> {
+    fn from_lua(value: mlua::Value, lua: &Lua) -> mlua::Result<Self> {
         let type_name = value.type_name();
 
         let conversion_error = || mlua::Error::FromLuaConversionError {
@@ -58,11 +223,118 @@ impl FromLua for Main {
         };
 
         let table = value.as_table().ok_or_else(conversion_error)?;
-        let color = table
-            .get::<Option<ColorChoice>>("color")?
-            .unwrap_or_default();
-        let skip: Option<mlua::Function> = table.get("skip")?;
-        let main = Main { color, skip };
+        let color = table.get::<Option<ColorChoice>>("color")?;
+        let charset = table.get::<Option<Charset>>("charset")?;
+        let function = |key: &str| {
+            table
+                .get::<Option<mlua::Function>>(key)
+                .map(|f| f.into_iter().collect::<Vec<_>>())
+        };
+        let skip = function("skip")?;
+        let on_finish = function("on_finish")?;
+        let filelimit = function("filelimit")?;
+        let max_entries = function("max_entries")?;
+        let hyperlink = function("hyperlink")?;
+        let sort = function("sort")?;
+        let main = Main {
+            color,
+            charset,
+            skip,
+            on_finish,
+            filelimit,
+            max_entries,
+            hyperlink,
+            sort,
+            errors: ErrorLog::from_lua(lua),
+        };
         Ok(main)
     }
 }
+
+/// The key that an entry is sorted by, returned by the `sort` function.
+///
+/// Numbers are sorted before text. Numbers are compared with [`f64::total_cmp`], so
+/// `NaN` is sorted after every other number.
+#[derive(Debug, Clone)]
+pub enum SortKey {
+    /// A number, like the size of a file.
+    Number(f64),
+    /// Text, like the name of a file.
+    Text(String),
+}
+
+impl PartialEq for SortKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SortKey {}
+
+impl PartialOrd for SortKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SortKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Number(a), Self::Number(b)) => a.total_cmp(b),
+            (Self::Number(_), Self::Text(_)) => Ordering::Less,
+            (Self::Text(_), Self::Number(_)) => Ordering::Greater,
+            (Self::Text(a), Self::Text(b)) => a.cmp(b),
+        }
+    }
+}
+
+impl FromLua for SortKey {
+    fn from_lua(value: mlua::Value, lua: &Lua) -> mlua::Result<Self> {
+        match value {
+            mlua::Value::Integer(_) | mlua::Value::Number(_) => {
+                f64::from_lua(value, lua).map(Self::Number)
+            }
+            mlua::Value::String(text) => Ok(Self::Text(text.to_string_lossy())),
+            value => Err(mlua::Error::FromLuaConversionError {
+                from: value.type_name(),
+                to: String::from("SortKey"),
+                message: Some(String::from("expected a string or a number")),
+            }),
+        }
+    }
+}
+
+impl IntoLua for &SortKey {
+    fn into_lua(self, lua: &Lua) -> mlua::Result<mlua::Value> {
+        match self {
+            SortKey::Number(number) => number.into_lua(lua),
+            SortKey::Text(text) => text.as_str().into_lua(lua),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_key_is_total_order() {
+        let mut keys = vec![
+            SortKey::Text(String::from("b")),
+            SortKey::Number(f64::NAN),
+            SortKey::Number(2.0),
+            SortKey::Text(String::from("a")),
+            SortKey::Number(1.0),
+        ];
+        keys.sort();
+
+        let expected = [
+            SortKey::Number(1.0),
+            SortKey::Number(2.0),
+            SortKey::Number(f64::NAN),
+            SortKey::Text(String::from("a")),
+            SortKey::Text(String::from("b")),
+        ];
+        assert_eq!(expected.as_slice(), keys.as_slice());
+    }
+}
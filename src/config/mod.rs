@@ -1,21 +1,31 @@
 //! This module provides utilities for configuration files.
-use crate::Result;
+use crate::{Error, Result};
+pub use check::CheckReport;
 pub use colors::Colors;
 use directories::ProjectDirs;
-pub use icons::Icons;
-pub use main::Main;
+pub use errors::ErrorLog;
+pub use icons::{IconSet, Icons};
+pub use main::{Main, SortKey};
 use mlua::{FromLuaMulti, Lua};
+pub use project::{ProjectConfig, TrustStore};
+use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+mod check;
 mod colors;
+mod errors;
 mod icons;
+mod init;
+mod lookup;
 mod main;
+mod modules;
+mod project;
 
 /// The project configuration directory.
 pub struct ConfigDir {
-    /// Utility for finding the project directories.
-    project_dirs: ProjectDirs,
+    /// The path to the directory.
+    path: PathBuf,
 }
 
 impl ConfigDir {
@@ -25,12 +35,27 @@ impl ConfigDir {
     const ORGANIZATION: &str = "";
     /// The project name.
     const APPLICATION: &str = env!("CARGO_PKG_NAME");
+    /// An environment variable the user can set to override the directory.
+    pub const ENV_VAR: &str = "FANCY_TREE_CONFIG_DIR";
 
-    /// The directory containing the config files.
-    pub fn new() -> Result<Self, &'static str> {
+    /// The directory containing the config files. This is the directory in
+    /// [`Self::ENV_VAR`] if it's set, or the user's configuration directory.
+    pub fn new() -> Result<Self> {
+        if let Some(path) = env::var_os(Self::ENV_VAR).filter(|path| !path.is_empty()) {
+            return Ok(Self::from_path(path));
+        }
         ProjectDirs::from(Self::QUALIFIER, Self::ORGANIZATION, Self::APPLICATION)
-            .map(|project_dirs| Self { project_dirs })
-            .ok_or("Missing home directory")
+            .map(|project_dirs| Self::from_path(project_dirs.config_dir()))
+            .ok_or(Error::MissingConfigDir)
+    }
+
+    /// Uses the directory at the path as the configuration directory.
+    #[inline]
+    pub fn from_path<P>(path: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self { path: path.into() }
     }
 
     /// Creates the configuration directory if it doesn't exist.
@@ -40,42 +65,50 @@ impl ConfigDir {
         Ok(())
     }
 
+    /// Loads the configuration files. If there's a unified `init.lua` file, the
+    /// separate files are ignored.
+    #[inline]
+    pub fn load(&self, lua: &Lua) -> Result<Configs> {
+        load_dir(lua, self.path())
+    }
+
     /// Loads the main configuration file.
     #[inline]
-    pub fn load_main(&self, lua: &Lua) -> mlua::Result<Option<Main>> {
+    pub fn load_main(&self, lua: &Lua) -> Result<Option<Main>> {
         self.load_file(lua)
     }
 
     /// Loads the icon configuration file.
     #[inline]
-    pub fn load_icons(&self, lua: &Lua) -> mlua::Result<Option<Icons>> {
+    pub fn load_icons(&self, lua: &Lua) -> Result<Option<Icons>> {
         self.load_file(lua)
     }
 
     /// Loads the colors configuration file.
     #[inline]
-    pub fn load_colors(&self, lua: &Lua) -> mlua::Result<Option<Colors>> {
+    pub fn load_colors(&self, lua: &Lua) -> Result<Option<Colors>> {
         self.load_file(lua)
     }
 
     /// Loads a `.lua` file from the configuration directory.
-    fn load_file<T>(&self, lua: &Lua) -> mlua::Result<Option<T>>
+    #[inline]
+    fn load_file<T>(&self, lua: &Lua) -> Result<Option<T>>
     where
         T: ConfigFile + FromLuaMulti,
     {
-        let path = self.path().join(T::FILENAME);
-        path.exists()
-            .then(|| {
-                let chunk = lua.load(path);
-                chunk.call::<T>(())
-            })
-            .transpose()
+        load_file(lua, self.path())
+    }
+
+    /// Gets the store of trusted project configurations.
+    #[inline]
+    pub fn trust_store(&self) -> TrustStore {
+        TrustStore::new(self.path().join(TrustStore::FILENAME))
     }
 
     /// Gets the config directory for the project.
     #[inline]
     pub fn path(&self) -> &Path {
-        self.project_dirs.config_dir()
+        &self.path
     }
 
     /// Gets the path of a file in the configuration directory from its filename.
@@ -105,10 +138,137 @@ impl ConfigDir {
     }
 }
 
+/// The configurations from one source, like the user's configuration directory.
+#[derive(Debug, Default, Clone)]
+pub struct Configs {
+    /// The main configuration.
+    pub main: Option<Main>,
+    /// The icon configuration.
+    pub icons: Option<Icons>,
+    /// The color configuration.
+    pub colors: Option<Colors>,
+}
+
+impl Configs {
+    /// Merges configurations on top of these, like a project's configurations on top
+    /// of the user's. See [`Main::merge`].
+    #[must_use]
+    pub fn merge(self, other: Self) -> Self {
+        /// Merges the configurations if both exist.
+        fn merge<T>(base: Option<T>, other: Option<T>, f: fn(T, T) -> T) -> Option<T> {
+            match (base, other) {
+                (Some(base), Some(other)) => Some(f(base, other)),
+                (base, other) => other.or(base),
+            }
+        }
+
+        Self {
+            main: merge(self.main, other.main, Main::merge),
+            icons: merge(self.icons, other.icons, Icons::merge),
+            colors: merge(self.colors, other.colors, Colors::merge),
+        }
+    }
+}
+
+/// Loads the configuration files from the directory. If there's a unified `init.lua`
+/// file, the separate files are ignored.
+fn load_dir(lua: &Lua, dir: &Path) -> Result<Configs> {
+    if let Some(configs) = load_file::<Configs>(lua, dir)? {
+        return Ok(configs);
+    }
+    let configs = Configs {
+        main: load_file(lua, dir)?,
+        icons: load_file(lua, dir)?,
+        colors: load_file(lua, dir)?,
+    };
+    Ok(configs)
+}
+
+/// Loads a `.lua` configuration file from the directory, if it exists.
+fn load_file<T>(lua: &Lua, dir: &Path) -> Result<Option<T>>
+where
+    T: ConfigFile + FromLuaMulti,
+{
+    let path = dir.join(T::FILENAME);
+    if !path.exists() {
+        return Ok(None);
+    }
+    T::from_path(lua, path).map(Some)
+}
+
 /// Common behavior for configuration files.
 pub trait ConfigFile {
     /// The filename in the configuration directory.
     const FILENAME: &'static str;
     /// The default lua module.
     const DEFAULT_MODULE: &'static str;
+
+    /// Creates the configuration from the source code of a Lua module, like
+    /// [`Self::DEFAULT_MODULE`].
+    ///
+    /// Errors refer to the module by [`Self::FILENAME`].
+    fn from_lua_str(lua: &Lua, source: &str) -> mlua::Result<Self>
+    where
+        Self: FromLuaMulti,
+    {
+        lua.load(source)
+            .set_name(format!("={}", Self::FILENAME))
+            .call::<Self>(())
+    }
+
+    /// Creates the configuration from the Lua module at the path.
+    ///
+    /// The module can `require` modules from the `lib/` directory next to it.
+    fn from_path<P>(lua: &Lua, path: P) -> Result<Self>
+    where
+        Self: FromLuaMulti,
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let lib_dir = path.parent().map(|dir| dir.join(modules::LIB_DIR));
+        modules::environment(lua, lib_dir)
+            .and_then(|env| lua.load(path).set_environment(env).call::<Self>(()))
+            .map_err(|source| Error::Config {
+                path: path.to_path_buf(),
+                source,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_modules_from_lua_str() {
+        let lua = Lua::new();
+        Main::from_lua_str(&lua, Main::DEFAULT_MODULE).expect("The default config should load");
+        Icons::from_lua_str(&lua, Icons::DEFAULT_MODULE).expect("The default icons should load");
+        Colors::from_lua_str(&lua, Colors::DEFAULT_MODULE).expect("The default colors should load");
+    }
+
+    #[test]
+    fn test_from_lua_str_error_names_file() {
+        let lua = Lua::new();
+        let err = Main::from_lua_str(&lua, "error('bad')").expect_err("The module should fail");
+        assert!(err.to_string().contains("config.lua:1: bad"));
+    }
+
+    #[test]
+    fn test_config_dir_from_path() {
+        let dir = tempfile::tempdir().expect("A temporary directory should be created");
+        fs::write(dir.path().join(Main::FILENAME), "return { color = 'off' }")
+            .expect("Fixture files should be created");
+        let config_dir = ConfigDir::from_path(dir.path());
+
+        let lua = Lua::new();
+        let configs = config_dir.load(&lua).expect("The configs should load");
+        let main = configs.main.expect("The main config should exist");
+        assert!(matches!(
+            main.color_choice(),
+            crate::color::ColorChoice::Off
+        ));
+        assert!(configs.icons.is_none());
+        assert!(configs.colors.is_none());
+    }
 }
@@ -0,0 +1,202 @@
+//! Module for the sandboxed `require` available to configuration files.
+//!
+//! The standard `package` library isn't loaded, and the base library's functions that
+//! load files are hidden, so configuration files can't load arbitrary files or native
+//! code. Instead, each configuration file gets a `require` that only resolves modules
+//! from the `lib/` directory next to it.
+use mlua::Lua;
+use std::path::{Path, PathBuf};
+
+/// The directory modules are resolved from, relative to the configuration directory.
+pub(super) const LIB_DIR: &str = "lib";
+
+/// Globals that configuration files can't use, because they load code outside of
+/// the `lib/` directory. Chunks from `load` run with the global table, so they could
+/// use the other functions. `_G` and Lua 5.1's `getfenv` are hidden too, because they
+/// would give access to the global table.
+const HIDDEN_GLOBALS: [&str; 5] = ["dofile", "loadfile", "load", "_G", "getfenv"];
+
+/// The registry key of the table of loaded modules.
+const LOADED_KEY: &str = "fancytree.loaded_modules";
+
+/// Creates the environment for a configuration file, with a `require` that resolves
+/// modules from `lib_dir`. Without a `lib_dir`, `require` always fails.
+///
+/// Other globals are read from the global table, except for the functions that load
+/// files.
+pub(super) fn environment(lua: &Lua, lib_dir: Option<PathBuf>) -> mlua::Result<mlua::Table> {
+    let env = lua.create_table()?;
+    let metatable = lua.create_table()?;
+    let globals = lua.globals();
+    // NOTE Setting a key to `nil` doesn't shadow it, so hidden globals are filtered
+    //      out when they're looked up instead.
+    let index = lua.create_function(move |_, (_, key): (mlua::Value, mlua::Value)| {
+        let is_hidden = key
+            .as_string()
+            .is_some_and(|key| HIDDEN_GLOBALS.iter().any(|hidden| key == *hidden));
+        if is_hidden {
+            return Ok(mlua::Nil);
+        }
+        globals.raw_get::<mlua::Value>(key)
+    })?;
+    metatable.set("__index", index)?;
+    env.set_metatable(Some(metatable))?;
+
+    let require = lua.create_function(move |lua, name: String| {
+        let Some(ref lib_dir) = lib_dir else {
+            return Err(mlua::Error::runtime(format!(
+                "can't require module '{name}': modules are only available to configuration directories"
+            )));
+        };
+        require(lua, lib_dir, &name)
+    })?;
+    env.set("require", require)?;
+    Ok(env)
+}
+
+/// Loads the module from the directory, or gets the module if it was already loaded.
+fn require(lua: &Lua, lib_dir: &Path, name: &str) -> mlua::Result<mlua::Value> {
+    let path = resolve(lib_dir, name)?;
+    let loaded = loaded_modules(lua)?;
+    let key = path.to_string_lossy();
+    match loaded.get::<mlua::Value>(key.as_ref())? {
+        mlua::Value::Nil => {}
+        // NOTE `false` marks a module that is still loading.
+        mlua::Value::Boolean(false) => {
+            return Err(mlua::Error::runtime(format!(
+                "loop while requiring module '{name}'"
+            )));
+        }
+        module => return Ok(module),
+    }
+
+    loaded.set(key.as_ref(), false)?;
+    // NOTE Modules can require other modules from the same directory.
+    let env = environment(lua, Some(lib_dir.to_path_buf()))?;
+    let result = lua
+        .load(path.as_path())
+        .set_environment(env)
+        .call::<mlua::Value>(name);
+    let module = match result {
+        // NOTE Like Lua's `require`, modules that return nothing are `true`.
+        Ok(mlua::Value::Nil) => mlua::Value::Boolean(true),
+        Ok(module) => module,
+        Err(err) => {
+            loaded.set(key.as_ref(), mlua::Nil)?;
+            return Err(err);
+        }
+    };
+    loaded.set(key.as_ref(), &module)?;
+    Ok(module)
+}
+
+/// Gets the table of loaded modules, shared by all configuration files.
+fn loaded_modules(lua: &Lua) -> mlua::Result<mlua::Table> {
+    if let Some(loaded) = lua.named_registry_value::<Option<mlua::Table>>(LOADED_KEY)? {
+        return Ok(loaded);
+    }
+    let loaded = lua.create_table()?;
+    lua.set_named_registry_value(LOADED_KEY, &loaded)?;
+    Ok(loaded)
+}
+
+/// Resolves a module name, like `theme` or `themes.dark`, to `NAME.lua` or
+/// `NAME/init.lua` in the directory.
+///
+/// Each part of the name can only have alphanumeric characters, `_`, and `-`, so
+/// modules can't be loaded from outside of the directory.
+fn resolve(lib_dir: &Path, name: &str) -> mlua::Result<PathBuf> {
+    let is_valid = name.split('.').all(|part| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    });
+    if !is_valid {
+        return Err(mlua::Error::runtime(format!(
+            "invalid module name '{name}'"
+        )));
+    }
+
+    let base = name
+        .split('.')
+        .fold(lib_dir.to_path_buf(), |path, part| path.join(part));
+    let candidates = [base.with_extension("lua"), base.join("init.lua")];
+    candidates
+        .iter()
+        .find(|path| path.is_file())
+        .cloned()
+        .ok_or_else(|| {
+            let tried = candidates
+                .iter()
+                .map(|path| format!("\n\tno file '{}'", path.display()))
+                .collect::<String>();
+            mlua::Error::runtime(format!("module '{name}' not found:{tried}"))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Runs the source with the environment of a configuration directory.
+    fn run(lua: &Lua, lib_dir: &Path, source: &str) -> mlua::Result<mlua::Value> {
+        let env = environment(lua, Some(lib_dir.to_path_buf()))?;
+        lua.load(source).set_environment(env).call(())
+    }
+
+    #[test]
+    fn test_require_caches_modules() {
+        let dir = tempfile::tempdir().expect("A temporary directory should be created");
+        fs::create_dir(dir.path().join("themes")).expect("Fixture directories should be created");
+        fs::write(
+            dir.path().join("themes/dark.lua"),
+            "count = (count or 0) + 1; return { count = count }",
+        )
+        .expect("Fixture files should be created");
+
+        let lua = Lua::new();
+        let source = "return require('themes.dark') == require('themes.dark')";
+        assert_eq!(
+            mlua::Value::Boolean(true),
+            run(&lua, dir.path(), source).expect("The modules should load")
+        );
+        let source = "return require('themes.dark').count";
+        assert_eq!(
+            mlua::Value::Integer(1),
+            run(&lua, dir.path(), source).expect("The module should be cached")
+        );
+    }
+
+    #[test]
+    fn test_require_rejects_paths_outside_of_directory() {
+        let dir = tempfile::tempdir().expect("A temporary directory should be created");
+        let lua = Lua::new();
+        for name in ["../secret", "/etc/passwd", "a..b", ""] {
+            let err = run(&lua, dir.path(), &format!("return require({name:?})"))
+                .expect_err("The module name should be invalid");
+            assert!(err.to_string().contains("invalid module name"), "{err}");
+        }
+        let err = run(&lua, dir.path(), "return require('missing')")
+            .expect_err("The module should be missing");
+        assert!(err.to_string().contains("module 'missing' not found"));
+    }
+
+    #[test]
+    fn test_file_loading_globals_are_hidden() {
+        let dir = tempfile::tempdir().expect("A temporary directory should be created");
+        let lua = Lua::new();
+        for name in HIDDEN_GLOBALS {
+            let value = run(&lua, dir.path(), &format!("return {name}"))
+                .expect("The global should be read");
+            assert_eq!(mlua::Nil, value, "`{name}` should be hidden");
+        }
+        let value = run(&lua, dir.path(), "return string.upper('a')")
+            .expect("Other globals should be available");
+        assert_eq!(
+            "A",
+            value.to_string().expect("The value should be a string")
+        );
+    }
+}
@@ -0,0 +1,390 @@
+//! Module for configuration files shipped with a project.
+use super::{Colors, Configs, Icons, Main, load_dir, modules};
+use crate::{Error, Result};
+use git2::{ObjectType, Oid};
+use mlua::{FromLua, Lua};
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A project's configuration, discovered from the root of a tree.
+///
+/// Project configurations run Lua code from the project, so they should only be loaded
+/// if the user trusts them (see [`TrustStore`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProjectConfig {
+    /// A `.fancy-tree/` directory, with the same files as the user's configuration
+    /// directory.
+    Dir(PathBuf),
+    /// A `.fancy-tree.lua` file, which returns a table with optional `config`, `icons`,
+    /// and `colors` keys.
+    File(PathBuf),
+}
+
+impl ProjectConfig {
+    /// The name of a project configuration directory.
+    const DIR_NAME: &str = ".fancy-tree";
+    /// The name of a project configuration file.
+    const FILE_NAME: &str = ".fancy-tree.lua";
+
+    /// Discovers the nearest project configuration, walking up from the root to the
+    /// `boundary` (like the git worktree root). If there's no boundary, or the root
+    /// isn't in it, only the root is searched.
+    ///
+    /// A `.fancy-tree/` directory takes priority over a `.fancy-tree.lua` file in the
+    /// same directory.
+    pub fn discover<P>(root: P, boundary: Option<&Path>) -> Option<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let root = root.as_ref().canonicalize().ok()?;
+        let start = if root.is_dir() {
+            root.as_path()
+        } else {
+            root.parent()?
+        };
+        let boundary = boundary
+            .and_then(|boundary| boundary.canonicalize().ok())
+            .filter(|boundary| start.starts_with(boundary));
+
+        for dir in start.ancestors() {
+            let config_dir = dir.join(Self::DIR_NAME);
+            if config_dir.is_dir() {
+                return Some(Self::Dir(config_dir));
+            }
+            let config_file = dir.join(Self::FILE_NAME);
+            if config_file.is_file() {
+                return Some(Self::File(config_file));
+            }
+            if boundary.as_deref().is_none_or(|boundary| dir == boundary) {
+                break;
+            }
+        }
+        None
+    }
+
+    /// Gets the path of the configuration directory or file.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Dir(path) | Self::File(path) => path,
+        }
+    }
+
+    /// Loads the configurations.
+    pub fn load(&self, lua: &Lua) -> Result<Configs> {
+        match self {
+            Self::Dir(dir) => load_dir(lua, dir),
+            Self::File(path) => Self::load_table(lua, path).map_err(|source| Error::Config {
+                path: path.clone(),
+                source,
+            }),
+        }
+    }
+
+    /// Loads the configurations from the table returned by a `.fancy-tree.lua` file.
+    fn load_table(lua: &Lua, path: &Path) -> mlua::Result<Configs> {
+        // NOTE Only the file is trusted, so it can't require modules.
+        let env = modules::environment(lua, None)?;
+        let Some(table) = lua
+            .load(path)
+            .set_environment(env)
+            .call::<Option<mlua::Table>>(())?
+        else {
+            return Ok(Configs::default());
+        };
+        let configs = Configs {
+            main: Option::<Main>::from_lua(table.get("config")?, lua)?,
+            icons: Option::<Icons>::from_lua(table.get("icons")?, lua)?,
+            colors: Option::<Colors>::from_lua(table.get("colors")?, lua)?,
+        };
+        Ok(configs)
+    }
+
+    /// Hashes the contents of the configuration, so that changes to a trusted
+    /// configuration need to be trusted again.
+    ///
+    /// For a directory, this includes every `.lua` file in the directory and its
+    /// `lib/` directory of modules.
+    pub fn hash(&self) -> io::Result<String> {
+        let (base, files) = match self {
+            Self::Dir(dir) => {
+                let mut files = lua_files(dir, false)?;
+                let lib_dir = dir.join(modules::LIB_DIR);
+                if lib_dir.is_dir() {
+                    files.extend(lua_files(&lib_dir, true)?);
+                }
+                files.sort();
+                (dir.as_path(), files)
+            }
+            Self::File(path) => (path.parent().unwrap_or(path), vec![path.clone()]),
+        };
+
+        // NOTE File names are included so that renaming a file changes the hash.
+        let mut contents = Vec::new();
+        for file in files {
+            let name = file.strip_prefix(base).unwrap_or(&file);
+            contents.extend_from_slice(name.as_os_str().as_encoded_bytes());
+            contents.push(0);
+            contents.extend(fs::read(&file)?);
+            contents.push(0);
+        }
+        Oid::hash_object(ObjectType::Blob, &contents)
+            .map(|oid| oid.to_string())
+            .map_err(io::Error::other)
+    }
+}
+
+/// Gets the `.lua` files in the directory, and in its subdirectories if `recursive`.
+///
+/// Symlinked subdirectories are followed like `require` follows them, but each
+/// directory is only read once, so symlinks can't create a loop.
+fn lua_files(dir: &Path, recursive: bool) -> io::Result<Vec<PathBuf>> {
+    /// Adds the files of the directory, unless the directory was already visited.
+    fn visit(
+        dir: &Path,
+        recursive: bool,
+        visited: &mut HashSet<PathBuf>,
+        files: &mut Vec<PathBuf>,
+    ) -> io::Result<()> {
+        if !visited.insert(dir.canonicalize()?) {
+            return Ok(());
+        }
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            // NOTE `is_dir` follows symlinks, unlike the entry's file type.
+            if recursive && path.is_dir() {
+                visit(&path, recursive, visited, files)?;
+            } else if path.extension().is_some_and(|ext| ext == "lua") {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    visit(dir, recursive, &mut HashSet::new(), &mut files)?;
+    Ok(files)
+}
+
+/// Remembers the project configurations that the user trusts, by path and hash.
+#[derive(Debug, Clone)]
+pub struct TrustStore {
+    /// The file the trusted configurations are stored in.
+    path: PathBuf,
+}
+
+impl TrustStore {
+    /// The filename of the store in the configuration directory.
+    pub(super) const FILENAME: &str = "trusted-projects";
+
+    /// Creates a store that uses the file.
+    #[inline]
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Is the project configuration trusted? Configurations that changed since they
+    /// were trusted aren't trusted.
+    pub fn is_trusted(&self, project: &ProjectConfig) -> io::Result<bool> {
+        let hash = project.hash()?;
+        let path = project.path().to_string_lossy();
+        let is_trusted = self
+            .entries()?
+            .iter()
+            .any(|(entry_hash, entry_path)| *entry_hash == hash && *entry_path == path);
+        Ok(is_trusted)
+    }
+
+    /// Trusts the project configuration, replacing the configuration's previous hash.
+    pub fn trust(&self, project: &ProjectConfig) -> io::Result<()> {
+        let hash = project.hash()?;
+        let path = project.path().to_string_lossy();
+        let mut entries = self.entries()?;
+        entries.retain(|(_, entry_path)| *entry_path != path);
+        entries.push((hash, path.into_owned()));
+
+        let contents = entries
+            .into_iter()
+            .map(|(hash, path)| format!("{hash} {path}\n"))
+            .collect::<String>();
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, contents)
+    }
+
+    /// Reads the hash and path of each trusted configuration.
+    fn entries(&self) -> io::Result<Vec<(String, String)>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        let entries = contents
+            .lines()
+            .filter_map(|line| line.split_once(' '))
+            .map(|(hash, path)| (hash.to_string(), path.to_string()))
+            .collect();
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover_stops_at_boundary() {
+        let dir = tempfile::tempdir().expect("A temporary directory should be created");
+        let root = dir.path().join("repo/src/nested");
+        fs::create_dir_all(&root).expect("Fixture directories should be created");
+        fs::write(dir.path().join(".fancy-tree.lua"), "return {}")
+            .expect("Fixture files should be created");
+
+        let boundary = dir.path().join("repo");
+        assert_eq!(None, ProjectConfig::discover(&root, Some(&boundary)));
+
+        fs::create_dir(boundary.join(".fancy-tree"))
+            .expect("Fixture directories should be created");
+        let expected = boundary
+            .join(".fancy-tree")
+            .canonicalize()
+            .expect("The directory should exist");
+        assert_eq!(
+            Some(ProjectConfig::Dir(expected)),
+            ProjectConfig::discover(&root, Some(&boundary))
+        );
+    }
+
+    #[test]
+    fn test_trust_store_requires_same_hash() {
+        let dir = tempfile::tempdir().expect("A temporary directory should be created");
+        let file = dir.path().join(".fancy-tree.lua");
+        fs::write(&file, "return {}").expect("Fixture files should be created");
+        let project = ProjectConfig::File(file.clone());
+        let store = TrustStore::new(dir.path().join("store/trusted-projects"));
+
+        assert!(
+            !store
+                .is_trusted(&project)
+                .expect("The store should be readable")
+        );
+        store.trust(&project).expect("The store should be writable");
+        assert!(
+            store
+                .is_trusted(&project)
+                .expect("The store should be readable")
+        );
+
+        fs::write(&file, "return { config = {} }").expect("Fixture files should be changed");
+        assert!(
+            !store
+                .is_trusted(&project)
+                .expect("The store should be readable")
+        );
+    }
+
+    #[test]
+    fn test_trusted_file_cannot_load_other_files() {
+        let dir = tempfile::tempdir().expect("A temporary directory should be created");
+        let file = dir.path().join(".fancy-tree.lua");
+        let evil = dir.path().join(".evil.lua");
+        fs::write(&evil, "return {}").expect("Fixture files should be created");
+        let store = TrustStore::new(dir.path().join("store/trusted-projects"));
+        let lua = Lua::new();
+
+        let loaders = [
+            format!("return dofile({evil:?})"),
+            format!("return loadfile({evil:?})()"),
+            format!("return load('return dofile')()({evil:?})"),
+        ];
+        for source in loaders {
+            fs::write(&file, &source).expect("Fixture files should be created");
+            let project = ProjectConfig::File(file.clone());
+            store.trust(&project).expect("The store should be writable");
+            fs::write(&evil, "error('untrusted code ran')")
+                .expect("Fixture files should be changed");
+
+            // NOTE The trusted file is unchanged, so it can't run the changed file.
+            assert!(
+                store
+                    .is_trusted(&project)
+                    .expect("The store should be readable")
+            );
+            let err = project
+                .load(&lua)
+                .expect_err("Loading other files should fail");
+            assert!(!err.to_string().contains("untrusted code ran"), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_changed_module_makes_config_untrusted() {
+        let dir = tempfile::tempdir().expect("A temporary directory should be created");
+        fs::create_dir(dir.path().join("lib")).expect("Fixture directories should be created");
+        fs::write(dir.path().join("config.lua"), "return require('theme')")
+            .expect("Fixture files should be created");
+        fs::write(dir.path().join("lib/theme.lua"), "return {}")
+            .expect("Fixture files should be created");
+        let project = ProjectConfig::Dir(dir.path().to_path_buf());
+        let store = TrustStore::new(dir.path().join("store/trusted-projects"));
+
+        store.trust(&project).expect("The store should be writable");
+        fs::write(
+            dir.path().join("lib/theme.lua"),
+            "error('untrusted code ran')",
+        )
+        .expect("Fixture files should be changed");
+        assert!(
+            !store
+                .is_trusted(&project)
+                .expect("The store should be readable")
+        );
+    }
+
+    #[test]
+    fn test_hash_includes_modules() {
+        let dir = tempfile::tempdir().expect("A temporary directory should be created");
+        let lib_dir = dir.path().join("lib/themes");
+        fs::create_dir_all(&lib_dir).expect("Fixture directories should be created");
+        fs::write(
+            dir.path().join("icons.lua"),
+            "return require('themes.dark')",
+        )
+        .expect("Fixture files should be created");
+        fs::write(lib_dir.join("dark.lua"), "return nil").expect("Fixture files should be created");
+        let project = ProjectConfig::Dir(dir.path().to_path_buf());
+
+        let before = project.hash().expect("The project should be hashed");
+        fs::write(lib_dir.join("dark.lua"), "return function() end")
+            .expect("Fixture files should be changed");
+        let after = project.hash().expect("The project should be hashed");
+        assert_ne!(before, after);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hash_follows_symlinked_module_directories() {
+        let dir = tempfile::tempdir().expect("A temporary directory should be created");
+        let project_dir = dir.path().join("project");
+        let themes_dir = dir.path().join("themes");
+        fs::create_dir_all(project_dir.join("lib")).expect("Fixture directories should be created");
+        fs::create_dir(&themes_dir).expect("Fixture directories should be created");
+        fs::write(themes_dir.join("dark.lua"), "return nil")
+            .expect("Fixture files should be created");
+        std::os::unix::fs::symlink(&themes_dir, project_dir.join("lib/themes"))
+            .expect("The symlink should be created");
+        // NOTE A symlink to an ancestor would loop forever if it was followed again.
+        std::os::unix::fs::symlink(&project_dir, project_dir.join("lib/loop"))
+            .expect("The symlink should be created");
+        let project = ProjectConfig::Dir(project_dir);
+
+        let before = project.hash().expect("The project should be hashed");
+        fs::write(themes_dir.join("dark.lua"), "return function() end")
+            .expect("Fixture files should be changed");
+        let after = project.hash().expect("The project should be hashed");
+        assert_ne!(before, after);
+    }
+}
@@ -0,0 +1,166 @@
+//! Module for the errors that can stop the application.
+use std::error::Error as StdError;
+use std::fmt::{self, Display};
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// An error that stops the application.
+///
+/// Each error maps to an exit code with [`Error::exit_code`]:
+///
+/// - `1`: the tree couldn't be read or written, or another runtime failure.
+/// - `2`: the arguments can't be used together (like clap's usage errors).
+/// - `3`: the configuration is invalid, or raised an error with `--strict-config`.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing failed.
+    Io(io::Error),
+    /// A file couldn't be read.
+    ReadFile {
+        /// The file that couldn't be read.
+        path: PathBuf,
+        /// Why the file couldn't be read.
+        source: io::Error,
+    },
+    /// The git repository couldn't be read.
+    Git(git2::Error),
+    /// The Lua state couldn't be created or used.
+    Lua(mlua::Error),
+    /// A configuration file couldn't be loaded.
+    Config {
+        /// The configuration file.
+        path: PathBuf,
+        /// Why the file couldn't be loaded.
+        source: mlua::Error,
+    },
+    /// The configuration directory couldn't be found, because there's no home
+    /// directory.
+    MissingConfigDir,
+    /// `--check-config` found this many problems.
+    ConfigProblems(usize),
+    /// A configuration callback raised an error with `--strict-config`.
+    StrictConfig,
+    /// Watching for changes failed.
+    Watch(notify::Error),
+    /// The editor for a configuration file couldn't be opened.
+    Editor(find_editor::Error),
+    /// The arguments can't be used together.
+    Usage(&'static str),
+}
+
+impl Error {
+    /// Gets the exit code to exit the application with.
+    pub fn exit_code(&self) -> ExitCode {
+        let code = match self {
+            Self::Usage(_) => 2,
+            Self::Config { .. } | Self::ConfigProblems(_) | Self::StrictConfig => 3,
+            _ => 1,
+        };
+        ExitCode::from(code)
+    }
+
+    /// Was the error caused by the reader of the output going away, like `| head`?
+    pub fn is_broken_pipe(&self) -> bool {
+        matches!(self, Self::Io(err) if err.kind() == io::ErrorKind::BrokenPipe)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{err}"),
+            Self::ReadFile { path, source } => {
+                write!(f, "couldn't read `{}`: {source}", path.display())
+            }
+            Self::Git(err) => write!(f, "couldn't read the git repository: {err}"),
+            Self::Lua(err) => write!(f, "Lua error: {err}"),
+            Self::Config { path, source } => write!(
+                f,
+                "invalid configuration `{}` (run with --check-config for details): {source}",
+                path.display()
+            ),
+            Self::MissingConfigDir => write!(
+                f,
+                "couldn't find the configuration directory (missing home directory)"
+            ),
+            Self::ConfigProblems(count) => {
+                write!(f, "found {count} problem(s) in the configuration")
+            }
+            Self::StrictConfig => {
+                write!(
+                    f,
+                    "a configuration callback raised an error (--strict-config)"
+                )
+            }
+            Self::Watch(err) => write!(f, "couldn't watch for changes: {err}"),
+            Self::Editor(err) => write!(f, "couldn't open the editor: {err}"),
+            Self::Usage(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Io(err) | Self::ReadFile { source: err, .. } => Some(err),
+            Self::Lua(err) | Self::Config { source: err, .. } => Some(err),
+            Self::Git(err) => Some(err),
+            Self::Watch(err) => Some(err),
+            Self::Editor(err) => Some(err),
+            Self::MissingConfigDir
+            | Self::ConfigProblems(_)
+            | Self::StrictConfig
+            | Self::Usage(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    #[inline]
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<git2::Error> for Error {
+    #[inline]
+    fn from(value: git2::Error) -> Self {
+        Self::Git(value)
+    }
+}
+
+impl From<mlua::Error> for Error {
+    #[inline]
+    fn from(value: mlua::Error) -> Self {
+        Self::Lua(value)
+    }
+}
+
+impl From<notify::Error> for Error {
+    #[inline]
+    fn from(value: notify::Error) -> Self {
+        Self::Watch(value)
+    }
+}
+
+impl From<find_editor::Error> for Error {
+    #[inline]
+    fn from(value: find_editor::Error) -> Self {
+        Self::Editor(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes() {
+        let err = Error::from(io::Error::from(io::ErrorKind::BrokenPipe));
+        assert!(err.is_broken_pipe());
+        assert_eq!(ExitCode::from(1), err.exit_code());
+        assert_eq!(ExitCode::from(2), Error::Usage("").exit_code());
+        assert_eq!(ExitCode::from(3), Error::StrictConfig.exit_code());
+    }
+}
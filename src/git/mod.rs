@@ -1,6 +1,7 @@
 //! Module for git integration.
-use git2::{Repository, StatusOptions};
+use git2::{AttrCheckFlags, AttrValue, Repository, StatusOptions};
 use status::StatusGetter;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use util::StatusEntryExt;
@@ -13,7 +14,10 @@ pub struct Git {
     /// The main repository.
     repository: Repository,
     /// Cached file statuses.
-    statuses: HashMap<PathBuf, git2::Status>,
+    ///
+    /// These can be reloaded while the git state is shared, like when watching for
+    /// changes.
+    statuses: RefCell<HashMap<PathBuf, git2::Status>>,
 }
 
 impl Git {
@@ -25,20 +29,26 @@ impl Git {
     where
         P: AsRef<Path>,
     {
-        let result = Repository::discover(root);
-        let repo_not_found = result
-            .as_ref()
-            .is_err_and(|err| matches!(err.code(), git2::ErrorCode::NotFound));
-        if repo_not_found {
-            Ok(None)
-        } else {
-            result.and_then(|repository| Self::from_repository(repository).map(Some))
+        Self::discover(root)?.map(Self::from_repository).transpose()
+    }
+
+    /// Discovers the repository containing the root.
+    ///
+    /// If the repository does not exist, this returns `Ok(None)`.
+    fn discover<P>(root: P) -> Result<Option<Repository>, git2::Error>
+    where
+        P: AsRef<Path>,
+    {
+        match Repository::discover(root) {
+            Ok(repository) => Ok(Some(repository)),
+            Err(err) if matches!(err.code(), git2::ErrorCode::NotFound) => Ok(None),
+            Err(err) => Err(err),
         }
     }
 
     /// Creates a Git struct from a git2 repository.
     fn from_repository(repository: Repository) -> Result<Self, git2::Error> {
-        let statuses = Self::statuses(&repository)?;
+        let statuses = RefCell::new(Self::statuses(&repository)?);
         let git = Self {
             repository,
             statuses,
@@ -57,6 +67,13 @@ impl Git {
         Ok(statuses)
     }
 
+    /// Reloads the cached file statuses, for example after files have changed.
+    pub fn reload_statuses(&self) -> Result<(), git2::Error> {
+        let statuses = Self::statuses(&self.repository)?;
+        self.statuses.replace(statuses);
+        Ok(())
+    }
+
     /// Creates the status options for fetching statuses.
     fn status_options() -> StatusOptions {
         let mut options = StatusOptions::new();
@@ -102,7 +119,7 @@ impl Git {
         P: AsRef<Path>,
     {
         let path = path.as_ref();
-        self.statuses.get(path).cloned()
+        self.statuses.borrow().get(path).cloned()
     }
 
     /// Gets the on-demand git2 status for a path.
@@ -122,6 +139,56 @@ impl Git {
         self.repository.is_path_ignored(path)
     }
 
+    /// Gets a boolean `linguist-*` attribute (like `linguist-generated`) for a path
+    /// from `.gitattributes`.
+    ///
+    /// Returns `None` if the attribute is unspecified or isn't a boolean value.
+    pub fn linguist_attribute<P>(&self, path: P, name: &str) -> Option<bool>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let value = self
+            .repository
+            .get_attr(path, name, AttrCheckFlags::default())
+            .ok()?;
+        match AttrValue::from_string(value) {
+            AttrValue::True => Some(true),
+            AttrValue::False => Some(false),
+            AttrValue::String("true") => Some(true),
+            AttrValue::String("false") => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Gets the path relative to the root of the repository's working tree, which is
+    /// necessary for git tools.
+    ///
+    /// Returns `None` if the path doesn't exist or isn't in the working tree.
+    pub fn relative_path<P>(&self, path: P) -> Option<PathBuf>
+    where
+        P: AsRef<Path>,
+    {
+        let git_root = self.root_dir()?;
+
+        // HACK Git root seems to have `/` separators, which breaks path cleanup on
+        //      Windows. This cleans up the git root so it can be used with
+        //      strip_prefix.
+        #[cfg(windows)]
+        let git_root = git_root.canonicalize().ok()?;
+
+        let path = path.as_ref();
+        let path = path.canonicalize().ok()?;
+        let path = path.strip_prefix(git_root).ok()?;
+        Some(path.to_path_buf())
+    }
+
+    /// Gets the ID of the commit that `HEAD` points to, if any.
+    pub fn head_commit(&self) -> Option<String> {
+        let commit = self.repository.head().ok()?.peel_to_commit().ok()?;
+        Some(commit.id().to_string())
+    }
+
     /// Gets the root directory of the git repository's working tree.
     ///
     /// Returns `None` for bare repositories.
@@ -130,3 +197,90 @@ impl Git {
         self.repository.workdir()
     }
 }
+
+/// The git states of multiple roots. Roots in the same repository share a git state, so
+/// the repository is only read once.
+#[derive(Default)]
+pub struct Repositories {
+    /// The git state of each discovered repository.
+    repositories: Vec<Git>,
+}
+
+impl Repositories {
+    /// Discovers the repository containing the root, reusing the git state if the
+    /// repository has already been discovered.
+    ///
+    /// Returns the index of the git state, or `Ok(None)` if the repository does not
+    /// exist.
+    pub fn discover<P>(&mut self, root: P) -> Result<Option<usize>, git2::Error>
+    where
+        P: AsRef<Path>,
+    {
+        let Some(repository) = Git::discover(root)? else {
+            return Ok(None);
+        };
+        let existing = self
+            .repositories
+            .iter()
+            .position(|git| git.repository.path() == repository.path());
+        if existing.is_some() {
+            return Ok(existing);
+        }
+        self.repositories.push(Git::from_repository(repository)?);
+        Ok(Some(self.repositories.len() - 1))
+    }
+
+    /// Gets the git state at the index from [`Self::discover`].
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&Git> {
+        self.repositories.get(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repositories_share_git_state() {
+        // NOTE Like the Lua git tests, this runs on this project's own repository, and
+        //      is skipped if the code isn't in a repository.
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let mut repositories = Repositories::default();
+        let Ok(Some(src)) = repositories.discover(root.join("src")) else {
+            eprintln!("Probably not a git repository");
+            return;
+        };
+        let git = repositories.discover(root.join("src/git"));
+        assert_eq!(Some(src), git.expect("The repository should be readable"));
+
+        let dir = tempfile::tempdir().expect("A temporary directory should be created");
+        let outside = repositories.discover(dir.path());
+        assert_eq!(None, outside.expect("Missing repositories aren't errors"));
+    }
+
+    #[test]
+    fn test_linguist_attribute() {
+        const GITATTRIBUTES: &str = "\
+gen.rs linguist-generated
+vendor/** linguist-vendored=false
+docs.md linguist-documentation=true
+notes.txt linguist-generated=maybe
+";
+
+        let dir = tempfile::tempdir().expect("A temporary directory should be created");
+        Repository::init(dir.path()).expect("The repository should be created");
+        std::fs::write(dir.path().join(".gitattributes"), GITATTRIBUTES)
+            .expect("The attributes should be written");
+        let git = Git::new(dir.path())
+            .expect("The repository should be readable")
+            .expect("The repository should exist");
+
+        let attribute = |path: &str, name: &str| git.linguist_attribute(path, name);
+        assert_eq!(Some(true), attribute("gen.rs", "linguist-generated"));
+        assert_eq!(Some(false), attribute("vendor/lib.rs", "linguist-vendored"));
+        assert_eq!(Some(true), attribute("docs.md", "linguist-documentation"));
+        assert_eq!(None, attribute("notes.txt", "linguist-generated"));
+        assert_eq!(None, attribute("other.rs", "linguist-generated"));
+    }
+}
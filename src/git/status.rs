@@ -3,7 +3,7 @@
 use mlua::{IntoLua, Lua};
 
 /// Git statuses (tracked/indexed or untracked/worktree) for a file.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Status {
     /// A new file.
     Added,
@@ -16,6 +16,9 @@ pub enum Status {
 }
 
 impl Status {
+    /// All git statuses.
+    pub const ALL: [Self; 4] = [Self::Added, Self::Modified, Self::Removed, Self::Renamed];
+
     /// Gets the string representation of a git status.
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -1,12 +1,13 @@
-use std::error::Error;
+pub use error::Error;
 pub use tree::Tree;
 
 pub mod cli;
 pub mod color;
 pub mod config;
+mod error;
 mod git;
 pub mod lua;
 pub mod tree;
 
 /// The standard result type.
-pub type Result<T = (), E = Box<dyn Error>> = core::result::Result<T, E>;
+pub type Result<T = (), E = Error> = core::result::Result<T, E>;
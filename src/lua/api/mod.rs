@@ -1,4 +1,5 @@
 //! Module for creating the `fancytree` API for Lua.
+use crate::config::IconSet;
 use mlua::Lua;
 
 mod path;
@@ -7,6 +8,8 @@ mod path;
 pub struct Builder {
     /// Adds `.path` API namespace when true.
     add_path_api: bool,
+    /// The icon set exposed as `.icon_set`.
+    icon_set: IconSet,
 }
 
 impl Builder {
@@ -15,18 +18,29 @@ impl Builder {
     pub fn new() -> Self {
         Self {
             add_path_api: false,
+            icon_set: IconSet::default(),
         }
     }
 
     /// Instructs the builder to add the `.path` namespace that provides path utilities.
     #[must_use]
     pub fn with_path(self) -> Self {
-        Self { add_path_api: true }
+        Self {
+            add_path_api: true,
+            ..self
+        }
+    }
+
+    /// Sets the icon set exposed to Lua.
+    #[must_use]
+    pub fn with_icon_set(self, icon_set: IconSet) -> Self {
+        Self { icon_set, ..self }
     }
 
     /// Builds the API table.
     pub fn build(self, lua: &Lua) -> mlua::Result<mlua::Table> {
         let api = Self::core(lua)?;
+        api.set("icon_set", self.icon_set)?;
         let path_api = self.add_path_api.then(|| path::create(lua)).transpose()?;
         api.set("path", path_api)?;
 
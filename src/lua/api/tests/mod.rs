@@ -1,3 +1,4 @@
+use crate::config::IconSet;
 use crate::git::Git;
 use crate::lua;
 use rstest::rstest;
@@ -35,6 +36,23 @@ fn test_path_glob_matches(#[case] module: &str) {
     assert_eq!(expected, actual);
 }
 
+#[rstest]
+#[case(IconSet::Nerd, "nerd")]
+#[case(IconSet::Ascii, "ascii")]
+#[case(IconSet::None, "none")]
+fn test_icon_set(#[case] icon_set: IconSet, #[case] expected: &str) {
+    let state = lua::state::Builder::new()
+        .with_icon_set(icon_set)
+        .build()
+        .expect("The Lua object should be valid");
+    let lua = state.to_inner();
+    let actual: String = lua
+        .load("return fancytree.icon_set")
+        .call(())
+        .expect("Chunk should run");
+    assert_eq!(expected, actual);
+}
+
 #[rstest]
 #[case(include_str!("./test_git_is_ignored_case_1.lua"))]
 #[case(include_str!("./test_git_is_ignored_case_2.lua"))]
@@ -68,7 +86,35 @@ fn test_git_is_ignored(#[case] module: &str) {
         .in_git_scope(|| {
             let (actual, expected): TestCase = chunk.call(()).expect("Chunk should run");
             assert_eq!(expected, actual);
-            Ok(())
+            Ok::<_, mlua::Error>(())
+        })
+        .expect("Lua-scoped function should succeed");
+}
+
+#[rstest]
+#[case(include_str!("./test_git_relative_path_case_1.lua"))]
+#[case(include_str!("./test_git_relative_path_case_2.lua"))]
+fn test_git_relative_path(#[case] module: &str) {
+    type TestCase = (Option<String>, Option<String>);
+
+    // NOTE Like the is_ignored tests, this runs on this project's own repository.
+    let Ok(Some(git)) = Git::new(env!("CARGO_MANIFEST_DIR")) else {
+        eprintln!("Probably not a git repository");
+        return;
+    };
+
+    let state = lua::state::Builder::new()
+        .with_git(&git)
+        .build()
+        .expect("The lua object should be valid");
+    let lua = state.to_inner();
+    let chunk = lua.load(module);
+
+    state
+        .in_git_scope(|| {
+            let (actual, expected): TestCase = chunk.call(()).expect("Chunk should run");
+            assert_eq!(expected, actual);
+            Ok::<_, mlua::Error>(())
         })
         .expect("Lua-scoped function should succeed");
 }
@@ -0,0 +1 @@
+return fancytree.git.relative_path("src/main.rs"), "src/main.rs"
@@ -0,0 +1 @@
+return fancytree.git.relative_path("this/path/does/not/exist"), nil
@@ -1,6 +1,7 @@
 //! Crate for interoperability between non-config types and the Lua config files.
-use crate::tree::Entry;
+use crate::git::status::Status;
 use crate::tree::entry::Attributes;
+use crate::tree::{self, Entry};
 use mlua::{IntoLua, Lua};
 use std::path::Path;
 
@@ -23,6 +24,30 @@ where
         self.0.is_executable()
     }
 
+    /// Is the file generated?
+    #[inline]
+    fn is_generated(&self) -> bool {
+        self.0.is_generated()
+    }
+
+    /// Is the file vendored?
+    #[inline]
+    fn is_vendored(&self) -> bool {
+        self.0.is_vendored()
+    }
+
+    /// Is the file documentation?
+    #[inline]
+    fn is_documentation(&self) -> bool {
+        self.0.is_documentation()
+    }
+
+    /// Does the file not exist on the filesystem?
+    #[inline]
+    fn is_virtual(&self) -> bool {
+        self.0.attributes().is_virtual()
+    }
+
     /// What is the file type (string enum)?
     fn file_type(&self) -> &str {
         const DIRECTORY: &str = "directory";
@@ -33,6 +58,8 @@ where
             Attributes::Directory(_) => DIRECTORY,
             Attributes::File(_) => FILE,
             Attributes::Symlink(_) => SYMLINK,
+            Attributes::Virtual(attributes) if attributes.is_directory() => DIRECTORY,
+            Attributes::Virtual(_) => FILE,
         }
     }
 
@@ -54,6 +81,10 @@ where
         let table = lua.create_table()?;
         table.set("is_hidden", self.is_hidden())?;
         table.set("is_executable", self.is_executable())?;
+        table.set("is_generated", self.is_generated())?;
+        table.set("is_vendored", self.is_vendored())?;
+        table.set("is_documentation", self.is_documentation())?;
+        table.set("is_virtual", self.is_virtual())?;
         table.set("file_type", self.file_type())?;
         table.set("language", self.language())?;
         let table = mlua::Value::Table(table);
@@ -70,3 +101,72 @@ where
         Self(value)
     }
 }
+
+/// Summary provides the totals of a tree.
+pub struct Summary<'a>(&'a tree::Summary);
+
+impl<'a> Summary<'a> {
+    /// Creates a table of counts for each git status.
+    fn git_status_table<F>(lua: &Lua, count: F) -> mlua::Result<mlua::Table>
+    where
+        F: Fn(Status) -> usize,
+    {
+        let table = lua.create_table()?;
+        for status in Status::ALL {
+            table.set(status, count(status))?;
+        }
+        Ok(table)
+    }
+
+    /// Creates a list of language totals, sorted from most bytes to least.
+    fn languages_table(&self, lua: &Lua) -> mlua::Result<mlua::Table> {
+        let languages = self
+            .0
+            .languages()
+            .sorted()
+            .into_iter()
+            .map(|(language, totals)| {
+                let table = lua.create_table()?;
+                table.set("name", language.name())?;
+                table.set("files", totals.files)?;
+                table.set("bytes", totals.bytes)?;
+                Ok(table)
+            });
+        let languages = languages.collect::<mlua::Result<Vec<_>>>()?;
+        lua.create_sequence_from(languages)
+    }
+}
+
+impl<'a> IntoLua for Summary<'a> {
+    fn into_lua(self, lua: &Lua) -> mlua::Result<mlua::Value> {
+        let summary = self.0;
+
+        let git = lua.create_table()?;
+        git.set(
+            "tracked",
+            Self::git_status_table(lua, |status| summary.tracked(status))?,
+        )?;
+        git.set(
+            "untracked",
+            Self::git_status_table(lua, |status| summary.untracked(status))?,
+        )?;
+
+        let table = lua.create_table()?;
+        table.set("directories", summary.directories())?;
+        table.set("files", summary.files())?;
+        table.set("size", summary.bytes())?;
+        table.set("hidden", summary.hidden())?;
+        table.set("ignored", summary.ignored())?;
+        table.set("git", git)?;
+        table.set("languages", self.languages_table(lua)?)?;
+        let table = mlua::Value::Table(table);
+        Ok(table)
+    }
+}
+
+impl<'a> From<&'a tree::Summary> for Summary<'a> {
+    #[inline]
+    fn from(value: &'a tree::Summary) -> Self {
+        Self(value)
+    }
+}
@@ -1,5 +1,6 @@
 //! Module for the state builder.
 use super::State;
+use crate::config::IconSet;
 use crate::git::Git;
 use crate::lua::api;
 use mlua::Lua;
@@ -8,18 +9,32 @@ use mlua::Lua;
 #[derive(Default)]
 pub struct Builder<'git> {
     git: Option<&'git Git>,
+    /// The icon set the tree is written with.
+    icon_set: IconSet,
 }
 
 impl<'git> Builder<'git> {
     /// Creates a new builder.
     pub fn new() -> Self {
-        Self { git: None }
+        Self {
+            git: None,
+            icon_set: IconSet::default(),
+        }
     }
 
     /// Adds git to the builder.
     #[must_use]
     pub fn with_git(self, git: &'git Git) -> Self {
-        Self { git: Some(git) }
+        Self {
+            git: Some(git),
+            ..self
+        }
+    }
+
+    /// Sets the icon set that's exposed to Lua as `fancytree.icon_set`.
+    #[must_use]
+    pub fn with_icon_set(self, icon_set: IconSet) -> Self {
+        Self { icon_set, ..self }
     }
 
     /// Builds the Lua state.
@@ -31,7 +46,10 @@ impl<'git> Builder<'git> {
 
         let inner = Lua::new_with(StdLib::TABLE | StdLib::STRING, LuaOptions::default())?;
 
-        let api = api::Builder::new().with_path().build(&inner)?;
+        let api = api::Builder::new()
+            .with_path()
+            .with_icon_set(self.icon_set)
+            .build(&inner)?;
 
         if self.git.is_some() {
             // NOTE We don't actually add any utilities here, because we need scoping.
@@ -1,7 +1,7 @@
 //! Module for creating a Lua state object for the application.
 use crate::git::Git;
 pub use builder::Builder;
-use mlua::Lua;
+use mlua::{IntoLua, Lua};
 use std::ffi::OsString;
 
 mod builder;
@@ -28,17 +28,37 @@ impl<'git> State<'git> {
     }
 
     /// Runs the function in a scope where git utilities are potentially available.
-    pub fn in_git_scope<T, F>(&self, f: F) -> mlua::Result<T>
+    pub fn in_git_scope<T, E, F>(&self, f: F) -> Result<T, E>
     where
-        F: FnOnce() -> mlua::Result<T>,
+        F: FnOnce() -> Result<T, E>,
+        E: From<mlua::Error>,
+    {
+        self.in_scope_of_git(self.git, f)
+    }
+
+    /// Runs the function in a scope where the git utilities use `git`, which can be a
+    /// different repository than the state was built with (e.g. for multiple roots).
+    /// If `git` is `None`, the git utilities are hidden.
+    pub fn in_scope_of_git<T, E, F>(&self, git: Option<&Git>, f: F) -> Result<T, E>
+    where
+        F: FnOnce() -> Result<T, E>,
+        E: From<mlua::Error>,
     {
         // HACK We can't build out the git API statically (like we can with the path
         //      API) because of lifetimes.
         // HACK Both git and git API must exist, so we can use a shortcut if neither exist.
-        let Some(git) = self.git else { return f() };
         let Some(git_api) = self.git_api()? else {
             return f();
         };
+        let Some(git) = git else {
+            // NOTE Roots outside of a repository shouldn't see another repository's
+            //      utilities.
+            let api = self.api()?;
+            api.set("git", mlua::Nil)?;
+            let result = f();
+            api.set("git", git_api)?;
+            return result;
+        };
 
         self.inner.scope(|scope| {
             let is_ignored = scope.create_function(|_lua, path: OsString| {
@@ -46,15 +66,27 @@ impl<'git> State<'git> {
                 Ok(is_ignored)
             })?;
             git_api.set("is_ignored", is_ignored)?;
-            f()
-        })
+            let head = scope.create_function(|_lua, ()| Ok(git.head_commit()))?;
+            git_api.set("head", head)?;
+            let relative_path = scope.create_function(|lua, path: OsString| {
+                git.relative_path(path)
+                    .map(|path| path.into_os_string().into_lua(lua))
+                    .transpose()
+            })?;
+            git_api.set("relative_path", relative_path)?;
+            // NOTE The function's error is passed through the scope's result.
+            Ok(f())
+        })?
+    }
+
+    /// Gets a reference to the API table.
+    fn api(&self) -> mlua::Result<mlua::Table> {
+        // TODO These hard-coded keys should be shared variables instead.
+        self.inner.globals().get::<mlua::Table>("fancytree")
     }
 
     /// Gets a reference to the git table.
     fn git_api(&self) -> mlua::Result<Option<mlua::Table>> {
-        let globals = self.inner.globals();
-        // TODO These hard-coded keys should be shared variables instead.
-        let api = globals.get::<mlua::Table>("fancytree")?;
-        api.get::<Option<mlua::Table>>("git")
+        self.api()?.get::<Option<mlua::Table>>("git")
     }
 }
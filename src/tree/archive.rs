@@ -0,0 +1,228 @@
+//! Module for reading the contents of archives as a tree.
+use super::entry::Attributes;
+use super::listing::Listing;
+use flate2::read::GzDecoder;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek};
+use std::path::{Component, Path, PathBuf};
+use tar::EntryType;
+use zip::ZipArchive;
+
+/// Mode bits for owner, group, and others execute permissions.
+const EXECUTABLE_BITS: u32 = 0o111;
+
+/// The supported archive formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// An uncompressed tarball.
+    Tar,
+    /// A gzip-compressed tarball. Crates are also gzip-compressed tarballs.
+    TarGz,
+    /// A zstd-compressed tarball.
+    TarZst,
+    /// A zip archive.
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// File name suffixes and their archive formats.
+    const SUFFIXES: [(&str, Self); 7] = [
+        (".tar", Self::Tar),
+        (".tar.gz", Self::TarGz),
+        (".tgz", Self::TarGz),
+        (".crate", Self::TarGz),
+        (".tar.zst", Self::TarZst),
+        (".tzst", Self::TarZst),
+        (".zip", Self::Zip),
+    ];
+
+    /// Detects the archive format from the path's file name.
+    pub fn detect<P>(path: P) -> Option<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let name = path.as_ref().file_name()?.to_str()?.to_ascii_lowercase();
+        Self::SUFFIXES
+            .into_iter()
+            .find(|(suffix, _)| name.ends_with(suffix))
+            .map(|(_, format)| format)
+    }
+
+    /// Reads the archive at the path into a listing rooted at the path.
+    pub fn read<P>(self, path: P) -> io::Result<Listing>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let file = BufReader::new(File::open(path)?);
+        match self {
+            Self::Tar => read_tar(path, file),
+            Self::TarGz => read_tar(path, GzDecoder::new(file)),
+            Self::TarZst => read_tar(path, zstd::Decoder::with_buffer(file)?),
+            Self::Zip => read_zip(path, file),
+        }
+    }
+}
+
+/// Reads a tarball into a listing rooted at `root`.
+fn read_tar<R>(root: &Path, reader: R) -> io::Result<Listing>
+where
+    R: Read,
+{
+    let mut listing = Listing::default();
+    let mut symlinks = Vec::new();
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        // NOTE Like zip's enclosed names, paths that could escape the archive's root
+        //      are skipped.
+        if !is_enclosed(&path) {
+            continue;
+        }
+
+        let header = entry.header();
+        let attributes = match header.entry_type() {
+            EntryType::Directory => Attributes::new_archived_directory(),
+            EntryType::Symlink => {
+                if let Some(target) = entry.link_name()? {
+                    symlinks.push((path.clone(), target.into_owned()));
+                }
+                Attributes::new_symlink(false)
+            }
+            EntryType::Regular | EntryType::Continuous | EntryType::Link => {
+                let executable = header.mode()? & EXECUTABLE_BITS != 0;
+                let size = entry.size();
+                Attributes::new_archived_file(
+                    root.join(&path),
+                    &path,
+                    &mut entry,
+                    size,
+                    executable,
+                )?
+            }
+            // NOTE Device files, FIFOs, and metadata headers aren't shown.
+            _ => continue,
+        };
+        listing.insert_with_attributes(root, &path, attributes, Attributes::new_archived_directory);
+    }
+    resolve_symlinks(root, &mut listing, symlinks);
+    Ok(listing)
+}
+
+/// Reads a zip archive into a listing rooted at `root`.
+fn read_zip<R>(root: &Path, reader: R) -> io::Result<Listing>
+where
+    R: Read + Seek,
+{
+    let mut listing = Listing::default();
+    let mut symlinks = Vec::new();
+    let mut archive = ZipArchive::new(reader)?;
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index)?;
+        let Some(path) = file.enclosed_name() else {
+            continue;
+        };
+
+        let attributes = if file.is_dir() {
+            Attributes::new_archived_directory()
+        } else if file.is_symlink() {
+            // NOTE A zip symlink's target is stored as its contents.
+            let mut target = String::new();
+            file.read_to_string(&mut target)?;
+            symlinks.push((path.clone(), PathBuf::from(target)));
+            Attributes::new_symlink(false)
+        } else {
+            let executable = file
+                .unix_mode()
+                .is_some_and(|mode| mode & EXECUTABLE_BITS != 0);
+            let size = file.size();
+            Attributes::new_archived_file(root.join(&path), &path, &mut file, size, executable)?
+        };
+        listing.insert_with_attributes(root, &path, attributes, Attributes::new_archived_directory);
+    }
+    resolve_symlinks(root, &mut listing, symlinks);
+    Ok(listing)
+}
+
+/// Marks the archived symlinks that point to directories in the archive. Each symlink
+/// is the path of the symlink and its target.
+///
+/// Targets are resolved after the whole archive is read, because they can come after
+/// the symlink in the archive.
+fn resolve_symlinks(root: &Path, listing: &mut Listing, symlinks: Vec<(PathBuf, PathBuf)>) {
+    for (path, target) in symlinks {
+        let Some(target) = resolve_link(&path, &target) else {
+            continue;
+        };
+        let target = root.join(target);
+        let target_is_directory = listing.has_children(&target)
+            || listing
+                .attributes(&target)
+                .is_some_and(Attributes::is_directory);
+        if target_is_directory {
+            listing.insert_with_attributes(
+                root,
+                &path,
+                Attributes::new_symlink(true),
+                Attributes::new_archived_directory,
+            );
+        }
+    }
+}
+
+/// Resolves the target of a symlink at the path, relative to the archive's root.
+///
+/// Returns `None` if the target is outside of the archive.
+fn resolve_link(path: &Path, target: &Path) -> Option<PathBuf> {
+    let parent = path.parent().unwrap_or(Path::new(""));
+    let mut resolved = PathBuf::new();
+    for component in parent.components().chain(target.components()) {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !resolved.pop() {
+                    return None;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(resolved)
+}
+
+/// Checks that the path can't escape the directory it's joined onto.
+fn is_enclosed(path: &Path) -> bool {
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("release.tar", Some(ArchiveFormat::Tar))]
+    #[case("release.tar.gz", Some(ArchiveFormat::TarGz))]
+    #[case("RELEASE.TGZ", Some(ArchiveFormat::TarGz))]
+    #[case("fancy-tree-0.1.1.crate", Some(ArchiveFormat::TarGz))]
+    #[case("release.tar.zst", Some(ArchiveFormat::TarZst))]
+    #[case("artifact.zip", Some(ArchiveFormat::Zip))]
+    #[case("main.rs", None)]
+    #[case("release.gz", None)]
+    fn test_archive_format_detect(#[case] path: &str, #[case] expected: Option<ArchiveFormat>) {
+        assert_eq!(expected, ArchiveFormat::detect(path));
+    }
+
+    #[rstest]
+    #[case("link", "real", Some("real"))]
+    #[case("./a/link", "../real/./b", Some("real/b"))]
+    #[case("a/link", "../../outside", None)]
+    #[case("link", "/etc", None)]
+    fn test_resolve_link(#[case] path: &str, #[case] target: &str, #[case] expected: Option<&str>) {
+        let expected = expected.map(PathBuf::from);
+        assert_eq!(expected, resolve_link(Path::new(path), Path::new(target)));
+    }
+}
@@ -1,9 +1,15 @@
 //! Provides tools for building a [`Tree`].
 use super::Tree;
 use super::charset::Charset;
+use super::compare::Comparison;
+use super::fit::Overflow;
+use super::hyperlink::HyperlinkChoice;
+use super::languages::LanguagesMode;
+use super::listing::Listing;
 use crate::color::ColorChoice;
 use crate::config;
 use crate::git::Git;
+use std::collections::HashSet;
 use std::path::Path;
 
 pub struct Builder<'git, 'charset, P: AsRef<Path>> {
@@ -17,6 +23,22 @@ pub struct Builder<'git, 'charset, P: AsRef<Path>> {
     config: Option<config::Main>,
     icons: Option<config::Icons>,
     colors: Option<config::Colors>,
+    hide_generated: bool,
+    hide_vendored: bool,
+    hide_documentation: bool,
+    languages: Option<LanguagesMode>,
+    report: bool,
+    filelimit: Option<usize>,
+    max_entries: Option<usize>,
+    directories_only: bool,
+    full_path: bool,
+    indent: bool,
+    listing: Option<Listing>,
+    archives: bool,
+    comparison: Option<Comparison>,
+    fit: Option<(usize, Overflow)>,
+    hyperlink: HyperlinkChoice,
+    errors: config::ErrorLog,
 }
 
 impl<'git, 'charset, P> Builder<'git, 'charset, P>
@@ -35,6 +57,22 @@ where
             config: None,
             icons: None,
             colors: None,
+            hide_generated: false,
+            hide_vendored: false,
+            hide_documentation: false,
+            languages: None,
+            report: true,
+            filelimit: None,
+            max_entries: None,
+            directories_only: false,
+            full_path: false,
+            indent: true,
+            listing: None,
+            archives: false,
+            comparison: None,
+            fit: None,
+            hyperlink: HyperlinkChoice::default(),
+            errors: config::ErrorLog::default(),
         }
     }
 
@@ -109,6 +147,159 @@ where
         }
     }
 
+    /// Sets if generated files should be hidden from the [`Tree`].
+    #[inline]
+    #[must_use]
+    pub fn hide_generated(self, hide: bool) -> Self {
+        Self {
+            hide_generated: hide,
+            ..self
+        }
+    }
+
+    /// Sets if vendored files should be hidden from the [`Tree`].
+    #[inline]
+    #[must_use]
+    pub fn hide_vendored(self, hide: bool) -> Self {
+        Self {
+            hide_vendored: hide,
+            ..self
+        }
+    }
+
+    /// Sets if documentation files should be hidden from the [`Tree`].
+    #[inline]
+    #[must_use]
+    pub fn hide_documentation(self, hide: bool) -> Self {
+        Self {
+            hide_documentation: hide,
+            ..self
+        }
+    }
+
+    /// Sets how language statistics are displayed for the [`Tree`].
+    #[inline]
+    #[must_use]
+    pub fn languages(self, mode: LanguagesMode) -> Self {
+        Self {
+            languages: Some(mode),
+            ..self
+        }
+    }
+
+    /// Sets if the summary report should be shown after the [`Tree`]. Defaults to
+    /// `true`.
+    #[inline]
+    #[must_use]
+    pub fn report(self, report: bool) -> Self {
+        Self { report, ..self }
+    }
+
+    /// Sets the maximum number of entries a directory can have for the [`Tree`] to
+    /// descend into it.
+    #[inline]
+    #[must_use]
+    pub fn filelimit(self, limit: usize) -> Self {
+        Self {
+            filelimit: Some(limit),
+            ..self
+        }
+    }
+
+    /// Sets the maximum number of entries to display for each directory in the
+    /// [`Tree`].
+    #[inline]
+    #[must_use]
+    pub fn max_entries(self, limit: usize) -> Self {
+        Self {
+            max_entries: Some(limit),
+            ..self
+        }
+    }
+
+    /// Sets if the [`Tree`] should only show directories.
+    #[inline]
+    #[must_use]
+    pub fn directories_only(self, directories_only: bool) -> Self {
+        Self {
+            directories_only,
+            ..self
+        }
+    }
+
+    /// Sets if the [`Tree`] should show each entry's path relative to the root
+    /// instead of its file name.
+    #[inline]
+    #[must_use]
+    pub fn full_path(self, full_path: bool) -> Self {
+        Self { full_path, ..self }
+    }
+
+    /// Sets if the [`Tree`] should indent entries with the [`Charset`]. Defaults to
+    /// `true`.
+    #[inline]
+    #[must_use]
+    pub fn indent(self, indent: bool) -> Self {
+        Self { indent, ..self }
+    }
+
+    /// Sets the [`Listing`] the [`Tree`] reads its hierarchy from instead of the
+    /// filesystem.
+    #[inline]
+    #[must_use]
+    pub fn listing(self, listing: Listing) -> Self {
+        Self {
+            listing: Some(listing),
+            ..self
+        }
+    }
+
+    /// Sets if the [`Tree`] should descend into archives like directories. An archive
+    /// at the root is always opened.
+    #[inline]
+    #[must_use]
+    pub fn archives(self, archives: bool) -> Self {
+        Self { archives, ..self }
+    }
+
+    /// Sets the [`Comparison`] of two directories for the [`Tree`] to show. The root
+    /// should be the comparison's right directory.
+    #[inline]
+    #[must_use]
+    pub fn comparison(self, comparison: Comparison) -> Self {
+        Self {
+            comparison: Some(comparison),
+            ..self
+        }
+    }
+
+    /// Sets the display width the [`Tree`] should fit names in, and how names that
+    /// don't fit are written.
+    #[inline]
+    #[must_use]
+    pub fn fit_to_width(self, width: usize, overflow: Overflow) -> Self {
+        Self {
+            fit: Some((width, overflow)),
+            ..self
+        }
+    }
+
+    /// Sets when the [`Tree`] should write entry names as hyperlinks. Defaults to
+    /// [`HyperlinkChoice::Never`].
+    #[inline]
+    #[must_use]
+    pub fn hyperlink(self, hyperlink: HyperlinkChoice) -> Self {
+        Self { hyperlink, ..self }
+    }
+
+    /// Sets the log for errors raised by configuration callbacks. If the log is strict,
+    /// writing the [`Tree`] fails after the first error.
+    #[inline]
+    #[must_use]
+    pub fn error_log(self, errors: config::ErrorLog) -> Self {
+        Self { errors, ..self }
+    }
+
     /// Creates the [`Tree`].
     pub fn build(self) -> Tree<'git, 'charset, P> {
         Tree {
@@ -120,6 +311,23 @@ where
             config: self.config.unwrap_or_default(),
             icons: self.icons.unwrap_or_default(),
             colors: self.colors.unwrap_or_default(),
+            hide_generated: self.hide_generated,
+            hide_vendored: self.hide_vendored,
+            hide_documentation: self.hide_documentation,
+            languages: self.languages,
+            report: self.report,
+            filelimit: self.filelimit,
+            max_entries: self.max_entries,
+            directories_only: self.directories_only,
+            full_path: self.full_path,
+            indent: self.indent,
+            listing: self.listing,
+            archives: self.archives,
+            comparison: self.comparison,
+            fit: self.fit,
+            hyperlinks: self.hyperlink.should_link(),
+            highlighted: HashSet::new(),
+            errors: self.errors,
         }
     }
 }
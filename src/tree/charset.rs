@@ -1,41 +1,122 @@
 //! Module for collections of `char`s.
+use clap::ValueEnum;
+use either::{Either, Left, Right};
+use mlua::{FromLua, Lua};
+use std::borrow::Cow;
+use unicode_width::UnicodeWidthStr;
 
 /// Provides text used for generating a tree. Could be considered the "branches" of the
 /// tree.
 ///
-/// When implementing this, ideally `depth`, `breadth`, and `indent` should all be the
-/// same visual length.
+/// When implementing this, ideally `depth`, `breadth`, `indent`, and `last` should all
+/// be the same visual length. [`Charset::new`] validates this.
 #[non_exhaustive]
+#[derive(Debug, Clone)]
 pub struct Charset<'a> {
     /// The text to print when traveling deeper into the directory structure.
     ///
     /// Typically should resemble a horizontal line.
-    pub depth: &'a str,
+    pub depth: Cow<'a, str>,
     /// The text to print when traversing the breadth of a directory.
     ///
     /// Typically a vertical line. Also helps control padding between branches.
-    pub breadth: &'a str,
+    pub breadth: Cow<'a, str>,
     /// The text to use to indent tree branches with each level.
-    pub indent: &'a str,
+    ///
+    /// This is used instead of `breadth` when the ancestor was the last entry of its
+    /// directory, so it should usually be blank.
+    pub indent: Cow<'a, str>,
+    /// The text to print when traveling deeper into the directory structure for the
+    /// last entry in a directory.
+    ///
+    /// Typically should resemble a corner.
+    pub last: Cow<'a, str>,
 }
 
 const EMPTY_TEXT: &str = "    ";
 
 impl<'a> Charset<'a> {
     /// The standard charset. Pretty characters, but not too fancy.
-    pub const STANDARD: Self = Self {
-        depth: "├── ",
+    pub const STANDARD: Self = Self::from_static(
+        "├── ",
         // NOTE U+00A0 is a non-breaking space
-        breadth: "│\u{00A0}\u{00A0} ",
-        indent: "    ",
-    };
+        "│\u{00A0}\u{00A0} ",
+        "    ",
+        "└── ",
+    );
+
+    /// ASCII charset. Useful for terminals and logs that don't support Unicode.
+    pub const ASCII: Self = Self::from_static("|-- ", "|   ", "    ", "`-- ");
+
+    /// Like the standard charset, but with a rounded corner for the last entry.
+    pub const ROUNDED: Self = Self::from_static("├── ", "│\u{00A0}\u{00A0} ", "    ", "╰── ");
+
+    /// Charset with heavy lines.
+    pub const HEAVY: Self = Self::from_static("┣━━ ", "┃\u{00A0}\u{00A0} ", "    ", "┗━━ ");
+
+    /// Charset with double lines.
+    pub const DOUBLE: Self = Self::from_static("╠══ ", "║\u{00A0}\u{00A0} ", "    ", "╚══ ");
 
     /// Empty charset. The tree is invisible.
-    pub const EMPTY: Self = Self {
-        depth: EMPTY_TEXT,
-        breadth: EMPTY_TEXT,
-        indent: EMPTY_TEXT,
-    };
+    pub const EMPTY: Self = Self::from_static(EMPTY_TEXT, EMPTY_TEXT, EMPTY_TEXT, EMPTY_TEXT);
+
+    /// Creates a new charset.
+    ///
+    /// Returns an error if the texts don't all have the same display width.
+    pub fn new<S>(depth: S, breadth: S, indent: S, last: S) -> Result<Self, WidthError>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let charset = Self {
+            depth: depth.into(),
+            breadth: breadth.into(),
+            indent: indent.into(),
+            last: last.into(),
+        };
+        charset.validate()?;
+        Ok(charset)
+    }
+
+    /// Gets the display width of each column.
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.depth.width()
+    }
+
+    /// Creates a charset from static strings without validation.
+    const fn from_static(
+        depth: &'static str,
+        breadth: &'static str,
+        indent: &'static str,
+        last: &'static str,
+    ) -> Self {
+        Self {
+            depth: Cow::Borrowed(depth),
+            breadth: Cow::Borrowed(breadth),
+            indent: Cow::Borrowed(indent),
+            last: Cow::Borrowed(last),
+        }
+    }
+
+    /// Checks that the texts all have the same display width.
+    fn validate(&self) -> Result<(), WidthError> {
+        let widths = [
+            ("depth", &self.depth),
+            ("breadth", &self.breadth),
+            ("indent", &self.indent),
+            ("last", &self.last),
+        ]
+        .map(|(name, text)| (name, text.width()));
+        let (_, expected) = widths[0];
+        match widths.iter().find(|(_, width)| *width != expected) {
+            None => Ok(()),
+            Some((name, width)) => Err(WidthError {
+                field: name,
+                width: *width,
+                expected,
+            }),
+        }
+    }
 }
 
 impl<'a> Default for Charset<'a> {
@@ -44,3 +125,124 @@ impl<'a> Default for Charset<'a> {
         Charset::STANDARD
     }
 }
+
+impl FromLua for Charset<'static> {
+    fn from_lua(value: mlua::Value, lua: &Lua) -> mlua::Result<Self> {
+        type PresetOrCustom = Either<String, mlua::Table>;
+        let type_name = value.type_name();
+        let make_conversion_error = |message: String| mlua::Error::FromLuaConversionError {
+            from: type_name,
+            to: String::from("Charset"),
+            message: Some(message),
+        };
+
+        match PresetOrCustom::from_lua(value, lua)? {
+            Left(name) => CharsetPreset::from_str(&name, false)
+                .map(Self::from)
+                .map_err(make_conversion_error),
+            Right(table) => {
+                let depth = table.get::<String>("depth")?;
+                let breadth = table.get::<String>("breadth")?;
+                let indent = table.get::<String>("indent")?;
+                let last = table.get::<String>("last")?;
+                Self::new(depth, breadth, indent, last)
+                    .map_err(|err| make_conversion_error(err.to_string()))
+            }
+        }
+    }
+}
+
+/// The built-in charsets.
+#[derive(Debug, ValueEnum, Clone, Copy, Default)]
+pub enum CharsetPreset {
+    /// Only ASCII characters (`|-- `).
+    Ascii,
+    /// Box-drawing characters (`├── `).
+    #[default]
+    Standard,
+    /// Box-drawing characters with a rounded corner (`╰── `).
+    Rounded,
+    /// Heavy box-drawing characters (`┣━━ `).
+    Heavy,
+    /// Double box-drawing characters (`╠══ `).
+    Double,
+    /// No visible characters, only indentation.
+    Empty,
+}
+
+impl From<CharsetPreset> for Charset<'static> {
+    fn from(value: CharsetPreset) -> Self {
+        match value {
+            CharsetPreset::Ascii => Charset::ASCII,
+            CharsetPreset::Standard => Charset::STANDARD,
+            CharsetPreset::Rounded => Charset::ROUNDED,
+            CharsetPreset::Heavy => Charset::HEAVY,
+            CharsetPreset::Double => Charset::DOUBLE,
+            CharsetPreset::Empty => Charset::EMPTY,
+        }
+    }
+}
+
+/// Error for when a charset's texts don't have the same display width.
+#[derive(Debug)]
+pub struct WidthError {
+    /// The name of the text with the wrong width.
+    field: &'static str,
+    /// The width of the text.
+    width: usize,
+    /// The expected width.
+    expected: usize,
+}
+
+impl std::fmt::Display for WidthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Charset `{}` has a display width of {}, but expected {}",
+            self.field, self.width, self.expected
+        )
+    }
+}
+
+impl std::error::Error for WidthError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::ascii(CharsetPreset::Ascii)]
+    #[case::standard(CharsetPreset::Standard)]
+    #[case::rounded(CharsetPreset::Rounded)]
+    #[case::heavy(CharsetPreset::Heavy)]
+    #[case::double(CharsetPreset::Double)]
+    #[case::empty(CharsetPreset::Empty)]
+    fn test_charset_preset_widths(#[case] preset: CharsetPreset) {
+        assert!(Charset::from(preset).validate().is_ok());
+    }
+
+    #[test]
+    fn test_charset_new_err() {
+        assert!(Charset::new("+- ", "|  ", "   ", "`-").is_err());
+    }
+
+    #[rstest]
+    #[case(r#"return "heavy""#)]
+    #[case(r#"return { depth = "+-", breadth = "| ", indent = "  ", last = "`-" }"#)]
+    fn test_charset_from_lua_ok(#[case] module: &str) {
+        let lua = Lua::new();
+        let charset = lua.load(module).call::<Charset>(());
+        assert!(charset.is_ok());
+    }
+
+    #[rstest]
+    #[case(r#"return "??unused??""#)]
+    #[case(r#"return { depth = "+-", breadth = "|", indent = "  ", last = "`-" }"#)]
+    #[case(r#"return { depth = "+-" }"#)]
+    fn test_charset_from_lua_err(#[case] module: &str) {
+        let lua = Lua::new();
+        let charset = lua.load(module).call::<Charset>(());
+        assert!(charset.is_err());
+    }
+}
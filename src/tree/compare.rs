@@ -0,0 +1,339 @@
+//! Module for comparing two directories as a merged tree.
+use super::entry::Attributes;
+use super::listing::Listing;
+use crate::git::status::Status;
+use clap::ValueEnum;
+use std::collections::{BTreeSet, HashMap};
+use std::fs::{self, File, Metadata};
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+/// Controls how files that exist in both directories are compared.
+#[derive(Debug, ValueEnum, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CompareBy {
+    /// Files are different if their sizes are different.
+    Size,
+    /// Files are different if their sizes or modification times are different.
+    Mtime,
+    /// Files are different if their sizes or contents are different.
+    #[default]
+    Content,
+}
+
+/// The merged tree of two directories, and how their entries differ.
+///
+/// The merged tree is rooted at the right directory. Entries that only exist in the
+/// left directory are added with their attributes from the left directory.
+pub struct Comparison {
+    /// The left directory.
+    left: PathBuf,
+    /// The right directory, which is the root of the merged tree.
+    right: PathBuf,
+    /// How files are compared.
+    compare_by: CompareBy,
+    /// Don't list the children of directories without differences?
+    collapse_unchanged: bool,
+    /// The maximum depth of directories to compare. The root has a depth of `0`.
+    max_level: Option<usize>,
+    /// The merged tree.
+    listing: Listing,
+    /// The differences of each entry, using the same statuses as git.
+    ///
+    /// Entries only in the left directory are removed, entries only in the right
+    /// directory are added, and entries in both that are different are modified.
+    statuses: HashMap<PathBuf, Status>,
+}
+
+/// A merged entry and its attributes, if they aren't read from the right directory.
+type MergedEntry = (PathBuf, Option<Attributes>);
+
+impl Comparison {
+    /// Compares the left and right directories.
+    ///
+    /// Like the tree, directories deeper than `max_level` aren't read, so differences
+    /// below them aren't detected.
+    pub fn new<L, R>(
+        left: L,
+        right: R,
+        compare_by: CompareBy,
+        collapse_unchanged: bool,
+        max_level: Option<usize>,
+    ) -> Self
+    where
+        L: AsRef<Path>,
+        R: AsRef<Path>,
+    {
+        let mut comparison = Self {
+            left: left.as_ref().to_path_buf(),
+            right: right.as_ref().to_path_buf(),
+            compare_by,
+            collapse_unchanged,
+            max_level,
+            listing: Listing::default(),
+            statuses: HashMap::new(),
+        };
+        let (_, entries) = comparison.compare_directory(Path::new(""), 0);
+        for (path, attributes) in entries {
+            match attributes {
+                Some(attributes) => comparison.listing.insert_with_attributes(
+                    &comparison.right,
+                    &path,
+                    attributes,
+                    Attributes::new_archived_directory,
+                ),
+                None => {
+                    comparison.listing.insert(&comparison.right, &path);
+                }
+            }
+        }
+        comparison
+    }
+
+    /// Gets the merged tree.
+    #[inline]
+    pub fn listing(&self) -> &Listing {
+        &self.listing
+    }
+
+    /// Gets how the entry at the path in the merged tree differs, if at all.
+    #[inline]
+    pub fn status<P>(&self, path: P) -> Option<Status>
+    where
+        P: AsRef<Path>,
+    {
+        self.statuses.get(path.as_ref()).copied()
+    }
+
+    /// Compares the children of the directory at the relative path. `depth` is the
+    /// depth of the directory.
+    ///
+    /// Returns if there are any differences, and the merged entries to list.
+    fn compare_directory(&mut self, relative: &Path, depth: usize) -> (bool, Vec<MergedEntry>) {
+        let is_max_level = self.max_level.is_some_and(|max| depth + 1 >= max);
+        let left = self.left.join(relative);
+        let right = self.right.join(relative);
+        // NOTE Directories that can't be read are treated like empty directories.
+        let names = [&left, &right]
+            .into_iter()
+            .flat_map(|dir| dir.read_dir().into_iter().flatten())
+            .filter_map(Result::ok)
+            .map(|entry| entry.file_name())
+            .collect::<BTreeSet<_>>();
+
+        let mut is_changed = false;
+        let mut entries = Vec::new();
+        for name in names {
+            let relative = relative.join(name);
+            let left_path = self.left.join(&relative);
+            let right_path = self.right.join(&relative);
+            // NOTE Symlinks aren't followed, so links to ancestors can't make the
+            //      comparison loop.
+            let left_metadata = fs::symlink_metadata(&left_path).ok();
+            let right_metadata = fs::symlink_metadata(&right_path).ok();
+
+            let is_directory = [&left_metadata, &right_metadata]
+                .into_iter()
+                .flatten()
+                .any(Metadata::is_dir);
+            let (is_children_changed, children) = if is_directory && !is_max_level {
+                self.compare_directory(&relative, depth + 1)
+            } else {
+                (false, Vec::new())
+            };
+
+            let status = match (&left_metadata, &right_metadata) {
+                (Some(_), None) => Some(Status::Removed),
+                (None, Some(_)) => Some(Status::Added),
+                (Some(left), Some(right)) if left.file_type() != right.file_type() => {
+                    Some(Status::Modified)
+                }
+                (Some(_), Some(_)) if is_children_changed => Some(Status::Modified),
+                (Some(left), Some(_)) if left.is_symlink() => (fs::read_link(&left_path).ok()
+                    != fs::read_link(&right_path).ok())
+                .then_some(Status::Modified),
+                (Some(left), Some(right)) if !is_directory => self
+                    .is_file_changed(&left_path, left, &right_path, right)
+                    .then_some(Status::Modified),
+                _ => None,
+            };
+            is_changed |= status.is_some();
+
+            // NOTE Entries only in the left directory don't exist in the right
+            //      directory, so their attributes need to be read from the left.
+            let attributes = if right_metadata.is_none() {
+                Attributes::new_relative_to(&left_path, &self.left).ok()
+            } else {
+                None
+            };
+            if let Some(status) = status {
+                self.statuses.insert(self.right.join(&relative), status);
+            }
+            entries.push((relative, attributes));
+            if status.is_some() || !self.collapse_unchanged {
+                entries.extend(children);
+            }
+        }
+        (is_changed, entries)
+    }
+
+    /// Checks if two files are different.
+    fn is_file_changed(
+        &self,
+        left_path: &Path,
+        left: &Metadata,
+        right_path: &Path,
+        right: &Metadata,
+    ) -> bool {
+        if left.len() != right.len() {
+            return true;
+        }
+        match self.compare_by {
+            CompareBy::Size => false,
+            CompareBy::Mtime => left.modified().ok() != right.modified().ok(),
+            // NOTE If either file can't be read, they're assumed to be different.
+            CompareBy::Content => !is_same_content(left_path, right_path).unwrap_or(false),
+        }
+    }
+}
+
+/// Checks if two files have the same contents.
+fn is_same_content(left: &Path, right: &Path) -> io::Result<bool> {
+    const CHUNK_SIZE: usize = 1024 * 8;
+
+    let mut left = BufReader::new(File::open(left)?);
+    let mut right = BufReader::new(File::open(right)?);
+    let mut left_buf = [0; CHUNK_SIZE];
+    let mut right_buf = [0; CHUNK_SIZE];
+    loop {
+        let n = read_chunk(&mut left, &mut left_buf)?;
+        let m = read_chunk(&mut right, &mut right_buf)?;
+        if left_buf[..n] != right_buf[..m] {
+            return Ok(false);
+        }
+        if n == 0 {
+            return Ok(true);
+        }
+    }
+}
+
+/// Fills the buffer as much as possible, returning the number of bytes read.
+fn read_chunk<R>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize>
+where
+    R: Read,
+{
+    let mut total = 0;
+    while total < buf.len() {
+        match reader.read(&mut buf[total..])? {
+            0 => break,
+            n => total += n,
+        }
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Creates the left and right directories.
+    fn fixture() -> (TempDir, TempDir) {
+        const LEFT: [(&str, &str); 4] = [
+            ("same.txt", "same"),
+            ("changed.txt", "left"),
+            ("left.txt", ""),
+            ("dir/same.txt", "same"),
+        ];
+        const RIGHT: [(&str, &str); 4] = [
+            ("same.txt", "same"),
+            ("changed.txt", "rght"),
+            ("right.txt", ""),
+            ("dir/same.txt", "same"),
+        ];
+
+        let create = |files: [(&str, &str); 4]| {
+            let dir = tempfile::tempdir().expect("A temporary directory should be created");
+            for (file, contents) in files {
+                let path = dir.path().join(file);
+                let parent = path.parent().expect("Fixture files should have a parent");
+                fs::create_dir_all(parent).expect("Fixture directories should be created");
+                fs::write(path, contents).expect("Fixture files should be created");
+            }
+            dir
+        };
+        (create(LEFT), create(RIGHT))
+    }
+
+    #[test]
+    fn test_comparison_statuses() {
+        let (left, right) = fixture();
+        let comparison =
+            Comparison::new(left.path(), right.path(), CompareBy::Content, false, None);
+        let status = |path: &str| comparison.status(right.path().join(path));
+
+        assert_eq!(None, status("same.txt"));
+        assert_eq!(Some(Status::Modified), status("changed.txt"));
+        assert_eq!(Some(Status::Removed), status("left.txt"));
+        assert_eq!(Some(Status::Added), status("right.txt"));
+        assert_eq!(None, status("dir"));
+    }
+
+    #[test]
+    fn test_comparison_compare_by_size() {
+        let (left, right) = fixture();
+        let comparison = Comparison::new(left.path(), right.path(), CompareBy::Size, false, None);
+        assert_eq!(None, comparison.status(right.path().join("changed.txt")));
+    }
+
+    #[test]
+    fn test_comparison_collapse_unchanged() {
+        let (left, right) = fixture();
+        let comparison = Comparison::new(left.path(), right.path(), CompareBy::Content, true, None);
+        assert!(!comparison.listing().has_children(right.path().join("dir")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_comparison_symlink_loops() {
+        let (left, right) = fixture();
+        for dir in [&left, &right] {
+            for name in ["l1", "l2"] {
+                std::os::unix::fs::symlink(".", dir.path().join(name))
+                    .expect("The symlink should be created");
+            }
+        }
+        std::os::unix::fs::symlink("dir", left.path().join("target"))
+            .expect("The symlink should be created");
+        std::os::unix::fs::symlink("same.txt", right.path().join("target"))
+            .expect("The symlink should be created");
+
+        let comparison =
+            Comparison::new(left.path(), right.path(), CompareBy::Content, false, None);
+        let status = |path: &str| comparison.status(right.path().join(path));
+        assert_eq!(None, status("l1"));
+        assert!(!comparison.listing().has_children(right.path().join("l1")));
+        assert_eq!(Some(Status::Modified), status("target"));
+    }
+
+    #[test]
+    fn test_comparison_max_level() {
+        let (left, right) = fixture();
+        fs::write(left.path().join("dir/changed.txt"), "left")
+            .expect("Fixture files should be created");
+        fs::write(right.path().join("dir/changed.txt"), "rght")
+            .expect("Fixture files should be created");
+
+        let comparison = Comparison::new(
+            left.path(),
+            right.path(),
+            CompareBy::Content,
+            false,
+            Some(1),
+        );
+        assert!(!comparison.listing().has_children(right.path().join("dir")));
+        assert_eq!(
+            None,
+            comparison.status(right.path().join("dir/changed.txt"))
+        );
+    }
+}
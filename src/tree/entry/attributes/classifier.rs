@@ -0,0 +1,112 @@
+//! Module for classifying files with gengo's heuristics.
+use gengo::{Builder, FileSource, Gengo};
+use std::error::Error;
+use std::iter;
+use std::path::{Component, Path, PathBuf};
+use std::sync::LazyLock;
+
+/// Shared instance of gengo, which is only used for its classification heuristics.
+static GENGO: LazyLock<Gengo<NoFiles>> = LazyLock::new(|| {
+    Builder::new(NoFiles)
+        .build()
+        .expect("Building gengo without a file source should not fail")
+});
+
+/// The classification of a file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Classification {
+    /// Is the file generated?
+    pub generated: bool,
+    /// Is the file vendored?
+    pub vendored: bool,
+    /// Is the file documentation?
+    pub documentation: bool,
+}
+
+impl Classification {
+    /// Classifies a file from its path and (possibly partial) contents.
+    pub fn new<P>(path: P, contents: &[u8]) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        let path = normalize(path.as_ref());
+        Self {
+            generated: GENGO.is_generated(&path, contents),
+            vendored: GENGO.is_vendored(&path, contents),
+            documentation: GENGO.is_documentation(&path, contents),
+        }
+    }
+}
+
+/// Removes `.` components from the path.
+///
+/// gengo's globs (like `dist/**`) expect simple relative paths, and won't match a
+/// path like `./dist/index.js`.
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .collect()
+}
+
+/// A file source without any files.
+///
+/// gengo only exposes its heuristics through [`Gengo`], which requires a file source.
+struct NoFiles;
+
+impl<'files> FileSource<'files> for NoFiles {
+    type Filepath = &'files Path;
+    type Contents = &'files [u8];
+    type Entry = ();
+    type State = ();
+    type Iter = iter::Empty<()>;
+
+    fn entries(&'files self) -> Result<Self::Iter, Box<dyn Error>> {
+        Ok(iter::empty())
+    }
+
+    fn filepath(
+        &'files self,
+        _entry: &Self::Entry,
+        _state: &mut Self::State,
+    ) -> Result<Self::Filepath, Box<dyn Error>> {
+        unreachable!("There are no entries")
+    }
+
+    fn contents(
+        &'files self,
+        _entry: &Self::Entry,
+        _state: &mut Self::State,
+    ) -> Result<Self::Contents, Box<dyn Error>> {
+        unreachable!("There are no entries")
+    }
+
+    fn state(&'files self) -> Result<Self::State, Box<dyn Error>> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("./dist/index.js", true, false, false)]
+    #[case("vendor.min.js", true, false, false)]
+    #[case("./node_modules/lib/index.js", false, true, false)]
+    #[case("docs/index.md", false, false, true)]
+    #[case("./src/main.rs", false, false, false)]
+    fn test_classification_new(
+        #[case] path: &str,
+        #[case] generated: bool,
+        #[case] vendored: bool,
+        #[case] documentation: bool,
+    ) {
+        let expected = Classification {
+            generated,
+            vendored,
+            documentation,
+        };
+        assert_eq!(expected, Classification::new(path, b""));
+    }
+}
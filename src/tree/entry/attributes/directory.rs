@@ -4,6 +4,7 @@ use super::interop::has_hidden_attribute;
 use std::fs::Metadata;
 
 /// Attributes for a directory.
+#[derive(Clone)]
 pub struct DirectoryAttributes {
     /// Is the directory hidden?
     hidden: bool,
@@ -18,6 +19,12 @@ impl DirectoryAttributes {
         }
     }
 
+    /// Creates attributes for a directory in an archive.
+    #[inline]
+    pub(super) const fn new_archived() -> Self {
+        Self { hidden: false }
+    }
+
     /// Is the directory hidden?
     #[inline]
     pub const fn is_hidden(&self) -> bool {
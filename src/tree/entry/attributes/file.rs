@@ -1,4 +1,5 @@
 //! Module for file attributes.
+use super::classifier::Classification;
 use super::interop::{has_hidden_attribute, is_executable};
 use gengo::Language;
 use std::fs::{File, Metadata};
@@ -9,6 +10,7 @@ use std::path::Path;
 const READ_LIMIT: u16 = 1024 * 16; // 16 KiB
 
 /// Attributes for a file.
+#[derive(Clone)]
 pub struct FileAttributes {
     /// Does the file have the hidden attribute set?
     ///
@@ -18,24 +20,61 @@ pub struct FileAttributes {
     language: Option<Language>,
     /// Is the file an executable?
     executable: bool,
+    /// The size of the file in bytes.
+    size: u64,
+    /// Is the file generated, vendored, and/or documentation?
+    classification: Classification,
 }
 
 impl FileAttributes {
-    /// Creates file attributes.
-    pub(super) fn new<P>(path: P, file: File, metadata: Metadata) -> io::Result<Self>
+    /// Creates file attributes. `relative_path` is the path relative to the tree's
+    /// root, and is used for classification.
+    pub(super) fn new<P>(
+        path: P,
+        relative_path: &Path,
+        file: File,
+        metadata: Metadata,
+    ) -> io::Result<Self>
     where
         P: AsRef<Path>,
     {
-        let mut contents = file.take(READ_LIMIT.into());
-        let mut buf = vec![0; READ_LIMIT.into()];
-        let n = contents.read(&mut buf)?;
-        buf.truncate(n);
+        let hidden = has_hidden_attribute(&metadata);
+        let executable = is_executable(&path, &metadata);
+        Self::from_reader(
+            path,
+            relative_path,
+            file,
+            metadata.len(),
+            hidden,
+            executable,
+        )
+    }
+
+    /// Creates file attributes from the file's contents, like a file in an archive.
+    /// See [`Self::new`] for `relative_path`.
+    pub(super) fn from_reader<P, R>(
+        path: P,
+        relative_path: &Path,
+        reader: R,
+        size: u64,
+        hidden: bool,
+        executable: bool,
+    ) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+        R: Read,
+    {
+        let mut buf = Vec::with_capacity(READ_LIMIT.into());
+        reader.take(READ_LIMIT.into()).read_to_end(&mut buf)?;
         let language = Language::pick(&path, &buf, READ_LIMIT.into());
+        let classification = Classification::new(relative_path, &buf);
 
         let attributes = FileAttributes {
-            hidden: has_hidden_attribute(&metadata),
+            hidden,
             language,
-            executable: is_executable(path, &metadata),
+            executable,
+            size,
+            classification,
         };
         Ok(attributes)
     }
@@ -52,9 +91,55 @@ impl FileAttributes {
         self.executable
     }
 
+    /// Gets the size of the file in bytes.
+    #[inline]
+    pub const fn size(&self) -> u64 {
+        self.size
+    }
+
     /// Get the file's language.
     #[inline]
     pub const fn language(&self) -> Option<Language> {
         self.language
     }
+
+    /// Is the file generated?
+    #[inline]
+    pub const fn is_generated(&self) -> bool {
+        self.classification.generated
+    }
+
+    /// Is the file vendored?
+    #[inline]
+    pub const fn is_vendored(&self) -> bool {
+        self.classification.vendored
+    }
+
+    /// Is the file documentation?
+    #[inline]
+    pub const fn is_documentation(&self) -> bool {
+        self.classification.documentation
+    }
+
+    /// Overrides the detected classification, for example with git attributes.
+    #[inline]
+    pub(crate) fn override_classification(&mut self, overrides: ClassificationOverrides) {
+        let classification = &mut self.classification;
+        classification.generated = overrides.generated.unwrap_or(classification.generated);
+        classification.vendored = overrides.vendored.unwrap_or(classification.vendored);
+        classification.documentation = overrides
+            .documentation
+            .unwrap_or(classification.documentation);
+    }
+}
+
+/// Optional overrides for a file's classification.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ClassificationOverrides {
+    /// Overrides if the file is generated.
+    pub generated: Option<bool>,
+    /// Overrides if the file is vendored.
+    pub vendored: Option<bool>,
+    /// Overrides if the file is documentation.
+    pub documentation: Option<bool>,
 }
@@ -1,17 +1,21 @@
 //! Provides utilities for file objects.
 pub use directory::DirectoryAttributes;
-pub use file::FileAttributes;
+pub use file::{ClassificationOverrides, FileAttributes};
 use std::fs::{self, File, Metadata};
-use std::io;
+use std::io::{self, Read};
 use std::path::Path;
 pub use symlink::SymlinkAttributes;
+pub use virtual_entry::VirtualAttributes;
 
+mod classifier;
 mod directory;
 mod file;
 mod interop;
 mod symlink;
+mod virtual_entry;
 
 /// Attributes for a tree entry.
+#[derive(Clone)]
 pub enum Attributes {
     /// A directory.
     Directory(DirectoryAttributes),
@@ -19,38 +23,88 @@ pub enum Attributes {
     File(FileAttributes),
     /// A symlink.
     Symlink(SymlinkAttributes),
+    /// An entry that doesn't exist on the filesystem.
+    Virtual(VirtualAttributes),
 }
 
 impl Attributes {
     /// Creates new [`Attributes`].
+    #[inline]
     pub fn new<P>(path: P) -> io::Result<Self>
     where
         P: AsRef<Path>,
+    {
+        Self::new_relative_to(path, "")
+    }
+
+    /// Creates new [`Attributes`] for a path within `root`. The path relative to `root`
+    /// is used to classify files (generated, vendored, etc.).
+    ///
+    /// Returns an [`io::ErrorKind::Unsupported`] error for other kinds of files, like
+    /// FIFOs.
+    pub fn new_relative_to<P, R>(path: P, root: R) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+        R: AsRef<Path>,
     {
         let path = path.as_ref();
-        let metadata = fs::metadata(path)?;
+        let relative_path = path.strip_prefix(root).unwrap_or(path);
+        let metadata = fs::symlink_metadata(path)?;
         let file_type = metadata.file_type();
 
         if file_type.is_symlink() {
-            Ok(Self::new_symlink())
+            Ok(Self::new_symlink(path.is_dir()))
         } else if file_type.is_dir() {
             Ok(Self::new_directory(metadata))
         } else if file_type.is_file() {
             let file = File::open(path)?;
-            Self::new_file(path, file, metadata)
+            Self::new_file(path, relative_path, file, metadata)
         } else {
-            // NOTE Just to make all file type checks a bit more explicit
-            unreachable!("Must be a symlink, directory, or file")
+            // NOTE FIFOs, sockets, and device files can't be read like files.
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("{} is not a file, directory, or symlink", path.display()),
+            ))
         }
     }
 
+    /// Creates attributes for a file in an archive from its contents. See
+    /// [`Self::new_relative_to`] for `relative_path`.
+    #[inline]
+    pub fn new_archived_file<P, R>(
+        path: P,
+        relative_path: &Path,
+        reader: R,
+        size: u64,
+        executable: bool,
+    ) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+        R: Read,
+    {
+        const HIDDEN: bool = false;
+        FileAttributes::from_reader(path, relative_path, reader, size, HIDDEN, executable)
+            .map(Self::File)
+    }
+
+    /// Creates attributes for a directory in an archive.
+    #[inline]
+    pub fn new_archived_directory() -> Self {
+        Self::Directory(DirectoryAttributes::new_archived())
+    }
+
     /// Creates file attributes.
     #[inline]
-    fn new_file<P>(path: P, file: File, metadata: Metadata) -> io::Result<Self>
+    fn new_file<P>(
+        path: P,
+        relative_path: &Path,
+        file: File,
+        metadata: Metadata,
+    ) -> io::Result<Self>
     where
         P: AsRef<Path>,
     {
-        FileAttributes::new(path, file, metadata).map(Self::File)
+        FileAttributes::new(path, relative_path, file, metadata).map(Self::File)
     }
 
     /// Creates directory attributes.
@@ -59,10 +113,17 @@ impl Attributes {
         Self::Directory(DirectoryAttributes::new(metadata))
     }
 
-    /// Creates symlink attributes.
+    /// Creates symlink attributes. `target_is_directory` is if the symlink points to
+    /// a directory.
     #[inline]
-    fn new_symlink() -> Self {
-        Self::Symlink(SymlinkAttributes)
+    pub fn new_symlink(target_is_directory: bool) -> Self {
+        Self::Symlink(SymlinkAttributes::new(target_is_directory))
+    }
+
+    /// Creates attributes for an entry that doesn't exist on the filesystem.
+    #[inline]
+    pub fn new_virtual(directory: bool) -> Self {
+        Self::Virtual(VirtualAttributes::new(directory))
     }
 
     /// Gets a reference to the file attributes.
@@ -95,11 +156,36 @@ impl Attributes {
         }
     }
 
+    /// Gets a reference to the virtual attributes.
+    #[inline]
+    pub fn virtual_entry(&self) -> Option<&VirtualAttributes> {
+        if let Self::Virtual(attributes) = self {
+            Some(attributes)
+        } else {
+            None
+        }
+    }
+
     /// Checks if the file is an executable.
     pub fn is_executable(&self) -> bool {
         self.is_file_and(|attributes| attributes.is_executable())
     }
 
+    /// Checks if the file is generated.
+    pub fn is_generated(&self) -> bool {
+        self.is_file_and(|attributes| attributes.is_generated())
+    }
+
+    /// Checks if the file is vendored.
+    pub fn is_vendored(&self) -> bool {
+        self.is_file_and(|attributes| attributes.is_vendored())
+    }
+
+    /// Checks if the file is documentation.
+    pub fn is_documentation(&self) -> bool {
+        self.is_file_and(|attributes| attributes.is_documentation())
+    }
+
     /// Checks if the attributes mark the file as hidden.
     pub fn is_hidden(&self) -> bool {
         self.is_file_and(|attributes| attributes.is_hidden())
@@ -124,10 +210,25 @@ impl Attributes {
         }
     }
 
-    /// Checks if the attributes are for a directory.
+    /// Checks if the attributes are for a directory. Virtual entries with children
+    /// are also directories.
     #[inline]
     pub const fn is_directory(&self) -> bool {
-        matches!(self, Self::Directory(_))
+        match self {
+            Self::Directory(_) => true,
+            Self::Virtual(attributes) => attributes.is_directory(),
+            Self::File(_) | Self::Symlink(_) => false,
+        }
+    }
+
+    /// Checks if the attributes are for a directory, or for a symlink that points to a
+    /// directory.
+    #[inline]
+    pub const fn is_directory_or_link(&self) -> bool {
+        match self {
+            Self::Symlink(attributes) => attributes.target_is_directory(),
+            _ => self.is_directory(),
+        }
     }
 
     /// If the attributes are for a directory, calls `f` on the [`DirectoryAttributes`].
@@ -147,4 +248,11 @@ impl Attributes {
     pub const fn is_symlink(&self) -> bool {
         matches!(self, Self::Symlink(_))
     }
+
+    /// Checks if the attributes are for an entry that doesn't exist on the
+    /// filesystem.
+    #[inline]
+    pub const fn is_virtual(&self) -> bool {
+        matches!(self, Self::Virtual(_))
+    }
 }
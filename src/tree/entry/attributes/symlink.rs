@@ -1,4 +1,24 @@
 //! Module for symlink attributes.
 
 /// Attributes for a symlink.
-pub struct SymlinkAttributes;
+#[derive(Clone)]
+pub struct SymlinkAttributes {
+    /// Does the symlink point to a directory?
+    target_is_directory: bool,
+}
+
+impl SymlinkAttributes {
+    /// Creates symlink attributes.
+    #[inline]
+    pub const fn new(target_is_directory: bool) -> Self {
+        Self {
+            target_is_directory,
+        }
+    }
+
+    /// Does the symlink point to a directory?
+    #[inline]
+    pub const fn target_is_directory(&self) -> bool {
+        self.target_is_directory
+    }
+}
@@ -0,0 +1,23 @@
+//! Module for virtual entry attributes.
+
+/// Attributes for an entry that doesn't exist on the filesystem, like a path from a
+/// listing.
+#[derive(Clone)]
+pub struct VirtualAttributes {
+    /// Does the entry have children?
+    directory: bool,
+}
+
+impl VirtualAttributes {
+    /// Creates new virtual attributes.
+    #[inline]
+    pub(super) const fn new(directory: bool) -> Self {
+        Self { directory }
+    }
+
+    /// Does the entry have children?
+    #[inline]
+    pub const fn is_directory(&self) -> bool {
+        self.directory
+    }
+}
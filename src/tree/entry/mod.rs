@@ -1,11 +1,13 @@
 //! Utilities for entries in a file tree.
 pub use attributes::Attributes;
+use attributes::ClassificationOverrides;
 use std::io;
 use std::path::Path;
 
 pub mod attributes;
 
 /// Represents an entry in a file tree and provides utilities for working with it.
+#[derive(Clone)]
 pub struct Entry<P: AsRef<Path>> {
     /// The path of this entry.
     path: P,
@@ -25,6 +27,32 @@ where
         Ok(entry)
     }
 
+    /// Creates a new [`Entry`] within the tree at `root`. See
+    /// [`Attributes::new_relative_to`].
+    #[inline]
+    pub fn new_relative_to<R>(path: P, root: R) -> io::Result<Self>
+    where
+        R: AsRef<Path>,
+    {
+        let attributes = Attributes::new_relative_to(path.as_ref(), root)?;
+        let entry = Self { path, attributes };
+        Ok(entry)
+    }
+
+    /// Creates a new [`Entry`] with known attributes, like an entry in an archive.
+    #[inline]
+    pub fn with_attributes(path: P, attributes: Attributes) -> Self {
+        Self { path, attributes }
+    }
+
+    /// Creates a new virtual [`Entry`] for a path that doesn't exist on the filesystem.
+    /// `directory` is if the entry has children.
+    #[inline]
+    pub fn new_virtual(path: P, directory: bool) -> Self {
+        let attributes = Attributes::new_virtual(directory);
+        Self { path, attributes }
+    }
+
     /// Gets the path of this entry.
     #[inline]
     pub fn path(&self) -> &Path {
@@ -43,6 +71,31 @@ where
         self.attributes.is_executable()
     }
 
+    /// Gets if the entry is a generated file.
+    #[inline]
+    pub fn is_generated(&self) -> bool {
+        self.attributes.is_generated()
+    }
+
+    /// Gets if the entry is a vendored file.
+    #[inline]
+    pub fn is_vendored(&self) -> bool {
+        self.attributes.is_vendored()
+    }
+
+    /// Gets if the entry is a documentation file.
+    #[inline]
+    pub fn is_documentation(&self) -> bool {
+        self.attributes.is_documentation()
+    }
+
+    /// Overrides the entry's classification if it is a file.
+    pub(crate) fn override_classification(&mut self, overrides: ClassificationOverrides) {
+        if let Attributes::File(ref mut attributes) = self.attributes {
+            attributes.override_classification(overrides);
+        }
+    }
+
     /// Is the file a dotfile?
     ///
     /// On Unix, this means that the file is hidden.
@@ -0,0 +1,94 @@
+//! Module for fitting names to the width of the terminal.
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// The text that replaces the middle of truncated names.
+const ELLIPSIS: &str = "…";
+
+/// Controls how names that are too wide are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Replace the middle of the name with an ellipsis.
+    Truncate,
+    /// Continue the name on the next line.
+    Wrap,
+}
+
+/// Replaces the middle of the text with an ellipsis so that it fits in the display
+/// width.
+pub(super) fn truncate(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    let Some(width) = width.checked_sub(ELLIPSIS.width()) else {
+        return String::new();
+    };
+    // NOTE The start of the name is usually more recognizable, so it gets the extra
+    //      column.
+    let tail_width = width / 2;
+    let head_width = width - tail_width;
+
+    let head_end =
+        take_width(text.char_indices(), head_width).map_or(0, |(index, c)| index + c.len_utf8());
+    let tail_start =
+        take_width(text.char_indices().rev(), tail_width).map_or(text.len(), |(index, _)| index);
+    format!("{}{ELLIPSIS}{}", &text[..head_end], &text[tail_start..])
+}
+
+/// Splits the text into lines that fit in the display width.
+///
+/// Characters that are wider than the width get their own line.
+pub(super) fn wrap(text: &str, width: usize) -> Vec<&str> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut line_width = 0;
+    for (index, c) in text.char_indices() {
+        let char_width = c.width().unwrap_or(0);
+        if line_width + char_width > width && index > start {
+            lines.push(&text[start..index]);
+            start = index;
+            line_width = 0;
+        }
+        line_width += char_width;
+    }
+    lines.push(&text[start..]);
+    lines
+}
+
+/// Gets the last character from the characters that fit in the display width.
+fn take_width<I>(chars: I, width: usize) -> Option<(usize, char)>
+where
+    I: Iterator<Item = (usize, char)>,
+{
+    let mut total = 0;
+    chars
+        .take_while(|(_, c)| {
+            total += c.width().unwrap_or(0);
+            total <= width
+        })
+        .last()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("main.rs", 10, "main.rs")]
+    #[case("long-file-name.txt", 10, "long-….txt")]
+    #[case("long-file-name.txt", 1, "…")]
+    #[case("long-file-name.txt", 0, "")]
+    #[case("日本語のファイル.txt", 11, "日本….txt")]
+    fn test_truncate(#[case] text: &str, #[case] width: usize, #[case] expected: &str) {
+        assert_eq!(expected, truncate(text, width));
+    }
+
+    #[rstest]
+    #[case("main.rs", 10, vec!["main.rs"])]
+    #[case("long-file-name.txt", 8, vec!["long-fil", "e-name.t", "xt"])]
+    #[case("日本語.txt", 4, vec!["日本", "語.t", "xt"])]
+    #[case("日本", 1, vec!["日", "本"])]
+    fn test_wrap(#[case] text: &str, #[case] width: usize, #[case] expected: Vec<&str>) {
+        assert_eq!(expected, wrap(text, width));
+    }
+}
@@ -0,0 +1,139 @@
+//! Module for linking entries with terminal hyperlinks (OSC 8).
+use clap::ValueEnum;
+use std::env;
+use std::io::{self, IsTerminal, Write, stdout};
+use std::path::Path;
+
+/// Controls when entries are written as hyperlinks.
+#[derive(Debug, ValueEnum, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HyperlinkChoice {
+    /// Link entries if stdout is a terminal that supports hyperlinks.
+    Auto,
+    /// Always link entries.
+    Always,
+    /// Never link entries.
+    #[default]
+    Never,
+}
+
+impl HyperlinkChoice {
+    /// Should entries be linked?
+    pub fn should_link(self) -> bool {
+        match self {
+            Self::Auto => stdout().is_terminal() && is_supported(),
+            Self::Always => true,
+            Self::Never => false,
+        }
+    }
+}
+
+/// Checks if the terminal supports hyperlinks.
+///
+/// There's no standard way to detect support, so this checks for terminals that are
+/// known to support them. `FORCE_HYPERLINK` can override the detection.
+fn is_supported() -> bool {
+    /// `TERM_PROGRAM` values of terminals that support hyperlinks.
+    const TERM_PROGRAMS: [&str; 5] = ["iTerm.app", "WezTerm", "vscode", "ghostty", "Hyper"];
+    /// Substrings of `TERM` values of terminals that support hyperlinks.
+    const TERMS: [&str; 5] = ["kitty", "alacritty", "foot", "ghostty", "wezterm"];
+    /// The first VTE version (0.50) that supports hyperlinks.
+    const MIN_VTE_VERSION: u32 = 5000;
+
+    if let Ok(force) = env::var("FORCE_HYPERLINK") {
+        return force != "0";
+    }
+    if env::var_os("CI").is_some() {
+        return false;
+    }
+    let has_var = [
+        "WT_SESSION",
+        "KONSOLE_VERSION",
+        "KITTY_WINDOW_ID",
+        "DOMTERM",
+    ]
+    .into_iter()
+    .any(|name| env::var_os(name).is_some());
+    let vte_version = env::var("VTE_VERSION")
+        .ok()
+        .and_then(|version| version.parse::<u32>().ok());
+    let term_program = env::var("TERM_PROGRAM").unwrap_or_default();
+    let term = env::var("TERM").unwrap_or_default();
+
+    has_var
+        || vte_version.is_some_and(|version| version >= MIN_VTE_VERSION)
+        || TERM_PROGRAMS.contains(&term_program.as_str())
+        || TERMS.iter().any(|name| term.contains(name))
+}
+
+/// Creates a `file://` URL for the absolute path of an existing path.
+pub fn file_url<P>(path: P) -> Option<String>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref().canonicalize().ok()?;
+    let path = path.as_os_str().as_encoded_bytes();
+
+    // HACK Canonical paths on Windows are verbatim paths like `\\?\C:\dir`, but URLs
+    //      need paths like `/C:/dir`.
+    #[cfg(windows)]
+    let converted = {
+        let path = path.strip_prefix(br"\\?\").unwrap_or(path);
+        let mut converted = vec![b'/'];
+        converted.extend(path.iter().map(|&b| if b == b'\\' { b'/' } else { b }));
+        converted
+    };
+    #[cfg(windows)]
+    let path = converted.as_slice();
+
+    // NOTE The host is left empty, which means the local host.
+    Some(format!("file://{}", percent_encode(path)))
+}
+
+/// Percent-encodes bytes for the path of a URL.
+fn percent_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len());
+    for &byte in bytes {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// Writes the start of a hyperlink to the URL. Text written after this is linked until
+/// [`write_end`] is called.
+pub(super) fn write_start<W>(writer: &mut W, url: &str) -> io::Result<()>
+where
+    W: Write,
+{
+    write!(writer, "\x1b]8;;{url}\x1b\\")
+}
+
+/// Writes the end of a hyperlink.
+pub(super) fn write_end<W>(writer: &mut W) -> io::Result<()>
+where
+    W: Write,
+{
+    write!(writer, "\x1b]8;;\x1b\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(b"/src/main.rs", "/src/main.rs")]
+    #[case(b"/my files/100%.txt", "/my%20files/100%25.txt")]
+    #[case("/日本.txt".as_bytes(), "/%E6%97%A5%E6%9C%AC.txt")]
+    fn test_percent_encode(#[case] bytes: &[u8], #[case] expected: &str) {
+        assert_eq!(expected, percent_encode(bytes));
+    }
+
+    #[test]
+    fn test_file_url_missing_path() {
+        assert_eq!(None, file_url("this/path/does/not/exist"));
+    }
+}
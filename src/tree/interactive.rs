@@ -0,0 +1,739 @@
+//! Module for browsing a tree interactively.
+use super::{Entry, Listing, Reveal, Tree};
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{
+    self, Clear, ClearType, DisableLineWrap, EnableLineWrap, EnterAlternateScreen,
+    LeaveAlternateScreen,
+};
+use crossterm::{execute, queue};
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write, stderr};
+use std::path::{Path, PathBuf};
+
+impl<'git, 'charset, P> Tree<'git, 'charset, P>
+where
+    P: AsRef<Path>,
+{
+    /// Browses the tree interactively. The browser is drawn on stderr, so that stdout
+    /// can be captured.
+    ///
+    /// Returns the path the user selected, if any.
+    pub fn browse(&self) -> io::Result<Option<PathBuf>> {
+        let mut browser = Browser::new(self);
+        let mut stderr = stderr();
+        let _terminal = TerminalGuard::enter(&mut stderr)?;
+        browser.run(&mut stderr)
+    }
+}
+
+/// Text showing the controls.
+const HELP: &str =
+    "↑↓ move  ←→ collapse/expand  / search  n next  . hidden  i ignored  ⏎ select  q quit";
+
+/// Puts the terminal in the state needed by the browser, and restores it when dropped.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    /// Enters raw mode and the alternate screen.
+    fn enter<W>(writer: &mut W) -> io::Result<Self>
+    where
+        W: Write,
+    {
+        terminal::enable_raw_mode()?;
+        // NOTE The guard is created first so that raw mode is disabled if this fails.
+        let guard = Self;
+        execute!(writer, EnterAlternateScreen, Hide, DisableLineWrap)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // NOTE Errors are ignored, since there's nothing else that can be done to
+        //      restore the terminal.
+        let _ = execute!(stderr(), EnableLineWrap, Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// A visible row in the browser.
+struct Row {
+    /// The entry for this row.
+    entry: Entry<PathBuf>,
+    /// The depth of the entry. The root has a depth of `0`.
+    depth: usize,
+    /// Tracks if each ancestor is the last child of its parent. See
+    /// [`Tree::write_depth`].
+    ancestors: Vec<bool>,
+    /// Is this the last child of its parent?
+    is_last: bool,
+    /// Can this entry be expanded?
+    is_directory: bool,
+    /// The path of the archive that the children of this entry are read from, if any.
+    archive: Option<PathBuf>,
+    /// The number of children, if the directory wasn't opened because it has more
+    /// children than the filelimit.
+    exceeded: Option<usize>,
+    /// If set, this row shows how many more children of its parent aren't shown,
+    /// instead of an entry. The entry is the parent's.
+    remaining: Option<usize>,
+}
+
+/// The children of a directory that has been expanded.
+#[derive(Clone)]
+struct Children {
+    /// The children that are shown.
+    entries: Vec<Entry<PathBuf>>,
+    /// The number of children that aren't shown because of the maximum entries.
+    remaining: usize,
+    /// The number of children, if there are more than the filelimit.
+    exceeded: Option<usize>,
+}
+
+/// What happens when the browser exits.
+#[derive(Debug, PartialEq, Eq)]
+enum Exit {
+    /// The user selected a path.
+    Select(PathBuf),
+    /// The user quit without selecting a path.
+    Quit,
+}
+
+/// The state of the interactive browser.
+struct Browser<'tree, 'git, 'charset, P: AsRef<Path>> {
+    /// The tree being browsed.
+    tree: &'tree Tree<'git, 'charset, P>,
+    /// The root entry.
+    root: Entry<PathBuf>,
+    /// The children of directories that have been expanded.
+    children: HashMap<PathBuf, Children>,
+    /// The contents of the archives that have been checked, or `None` if they aren't
+    /// opened.
+    archives: HashMap<PathBuf, Option<Listing>>,
+    /// The paths of expanded directories.
+    expanded: HashSet<PathBuf>,
+    /// The visible rows.
+    rows: Vec<Row>,
+    /// The index of the selected row.
+    selected: usize,
+    /// The index of the first row on the screen.
+    scroll: usize,
+    /// Skipped entries that are shown anyway.
+    reveal: Reveal,
+    /// The search query while it is being typed.
+    search: Option<String>,
+    /// The last search query, used to jump to the next match.
+    query: String,
+}
+
+impl<'tree, 'git, 'charset, P> Browser<'tree, 'git, 'charset, P>
+where
+    P: AsRef<Path>,
+{
+    /// Creates a browser with the root expanded.
+    fn new(tree: &'tree Tree<'git, 'charset, P>) -> Self {
+        let path = tree.root.as_ref().to_path_buf();
+        let root = Entry::new(path.clone()).unwrap_or_else(|_| Entry::new_virtual(path, true));
+        let expanded = HashSet::from([root.path().to_path_buf()]);
+        let mut browser = Self {
+            tree,
+            root,
+            children: HashMap::new(),
+            archives: HashMap::new(),
+            expanded,
+            rows: Vec::new(),
+            selected: 0,
+            scroll: 0,
+            reveal: Reveal::default(),
+            search: None,
+            query: String::new(),
+        };
+        browser.rebuild();
+        browser
+    }
+
+    /// Handles input until the user exits.
+    fn run<W>(&mut self, writer: &mut W) -> io::Result<Option<PathBuf>>
+    where
+        W: Write,
+    {
+        loop {
+            self.draw(writer)?;
+            // NOTE Other events, like resizing, just redraw the browser.
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            match self.handle_key(key) {
+                Some(Exit::Select(path)) => return Ok(Some(path)),
+                Some(Exit::Quit) => return Ok(None),
+                None => {}
+            }
+        }
+    }
+
+    /// Updates the browser for the key press.
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Exit> {
+        if key.kind != KeyEventKind::Press {
+            return None;
+        }
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Some(Exit::Quit);
+        }
+        if self.search.is_some() {
+            self.handle_search_key(key);
+            return None;
+        }
+
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.select(self.selected.saturating_sub(1)),
+            KeyCode::Down | KeyCode::Char('j') => self.select(self.selected + 1),
+            KeyCode::PageUp => self.select(self.selected.saturating_sub(self.page_size())),
+            KeyCode::PageDown => self.select(self.selected + self.page_size()),
+            KeyCode::Home | KeyCode::Char('g') => self.select(0),
+            KeyCode::End | KeyCode::Char('G') => self.select(usize::MAX),
+            KeyCode::Right | KeyCode::Char('l') => self.expand(),
+            KeyCode::Left | KeyCode::Char('h') => self.collapse(),
+            KeyCode::Backspace | KeyCode::Char('u') => self.select_parent(),
+            KeyCode::Char('/') => self.search = Some(String::new()),
+            KeyCode::Char('n') => self.find_next(self.selected + 1),
+            KeyCode::Char('.') => {
+                self.reveal.hidden = !self.reveal.hidden;
+                self.reload();
+            }
+            KeyCode::Char('i') => {
+                self.reveal.ignored = !self.reveal.ignored;
+                self.reload();
+            }
+            KeyCode::Enter => {
+                let path = self.rows[self.selected].entry.path().to_path_buf();
+                return Some(Exit::Select(path));
+            }
+            KeyCode::Esc | KeyCode::Char('q') => return Some(Exit::Quit),
+            _ => {}
+        }
+        None
+    }
+
+    /// Updates the search query for the key press, selecting the first match.
+    fn handle_search_key(&mut self, key: KeyEvent) {
+        let Some(ref mut search) = self.search else {
+            return;
+        };
+        match key.code {
+            KeyCode::Char(c) => search.push(c),
+            KeyCode::Backspace => {
+                search.pop();
+            }
+            KeyCode::Enter | KeyCode::Esc => {
+                self.search = None;
+                return;
+            }
+            _ => return,
+        }
+        self.query = search.clone();
+        self.find_next(self.selected);
+    }
+
+    /// Selects the next row at or after `start` with a name that contains the query,
+    /// wrapping around to the top.
+    fn find_next(&mut self, start: usize) {
+        if self.query.is_empty() {
+            return;
+        }
+        let query = self.query.to_lowercase();
+        let count = self.rows.len();
+        let found = (0..count)
+            .map(|offset| (start + offset) % count)
+            .filter(|index| self.rows[*index].remaining.is_none())
+            .find(|index| {
+                let path = self.rows[*index].entry.path();
+                path.file_name()
+                    .unwrap_or(path.as_os_str())
+                    .to_string_lossy()
+                    .to_lowercase()
+                    .contains(&query)
+            });
+        if let Some(index) = found {
+            self.select(index);
+        }
+    }
+
+    /// Selects the row at the index, clamped to the visible rows.
+    fn select(&mut self, index: usize) {
+        self.selected = index.min(self.rows.len().saturating_sub(1));
+    }
+
+    /// Expands the selected directory, or selects its first child if it is already
+    /// expanded.
+    fn expand(&mut self) {
+        let row = &self.rows[self.selected];
+        if !row.is_directory {
+            return;
+        }
+        let path = row.entry.path().to_path_buf();
+        if self.expanded.insert(path) {
+            self.rebuild();
+        } else if self
+            .rows
+            .get(self.selected + 1)
+            .is_some_and(|child| child.depth > row.depth)
+        {
+            self.select(self.selected + 1);
+        }
+    }
+
+    /// Collapses the selected directory, or selects its parent if it isn't expanded.
+    fn collapse(&mut self) {
+        let row = &self.rows[self.selected];
+        if row.is_directory && self.expanded.remove(row.entry.path()) {
+            self.rebuild();
+        } else {
+            self.select_parent();
+        }
+    }
+
+    /// Selects the parent of the selected row.
+    fn select_parent(&mut self) {
+        let depth = self.rows[self.selected].depth;
+        let parent = self.rows[..self.selected]
+            .iter()
+            .rposition(|row| row.depth < depth);
+        if let Some(index) = parent {
+            self.select(index);
+        }
+    }
+
+    /// Clears the cached children and rebuilds the rows, for example when the revealed
+    /// entries change.
+    fn reload(&mut self) {
+        self.children.clear();
+        self.rebuild();
+    }
+
+    /// Rebuilds the visible rows from the expanded directories, keeping the selected
+    /// path selected if it is still visible.
+    fn rebuild(&mut self) {
+        let selected = self
+            .rows
+            .get(self.selected)
+            .map(|row| row.entry.path().to_path_buf());
+
+        let root = self.new_row(self.root.clone(), 0, Vec::new(), true, None);
+        let mut rows = vec![root];
+        self.push_children(&mut rows, 0);
+        self.rows = rows;
+
+        let index = selected.and_then(|selected| {
+            self.rows
+                .iter()
+                .position(|row| row.entry.path() == selected)
+        });
+        self.select(index.unwrap_or(self.selected));
+    }
+
+    /// Adds the rows for the children of the row at the index if it is expanded, and
+    /// their expanded descendants.
+    fn push_children(&mut self, rows: &mut Vec<Row>, index: usize) {
+        let parent = &rows[index];
+        let path = parent.entry.path().to_path_buf();
+        if !parent.is_directory || !self.expanded.contains(&path) {
+            return;
+        }
+        let depth = parent.depth + 1;
+        let ancestors = if parent.depth == 0 {
+            Vec::new()
+        } else {
+            let mut ancestors = parent.ancestors.clone();
+            ancestors.push(parent.is_last);
+            ancestors
+        };
+
+        let parent_entry = parent.entry.clone();
+        let archive = parent.archive.clone();
+        let children = self.load_children(&parent_entry, archive.as_ref());
+        if let Some(count) = children.exceeded {
+            rows[index].exceeded = Some(count);
+            return;
+        }
+
+        // NOTE Like in the static tree, the line showing how many children remain is
+        //      the last child.
+        let count = children.entries.len();
+        let has_remaining = children.remaining > 0;
+        for (child_index, entry) in children.entries.into_iter().enumerate() {
+            let is_last = !has_remaining && child_index + 1 == count;
+            let row = self.new_row(entry, depth, ancestors.clone(), is_last, archive.clone());
+            rows.push(row);
+            self.push_children(rows, rows.len() - 1);
+        }
+        if has_remaining {
+            rows.push(Row {
+                entry: parent_entry,
+                depth,
+                ancestors,
+                is_last: true,
+                is_directory: false,
+                archive: None,
+                exceeded: None,
+                remaining: Some(children.remaining),
+            });
+        }
+    }
+
+    /// Creates the row for an entry. `archive` is the archive that the entry was read
+    /// from, if any.
+    ///
+    /// Like in the static tree, archives are opened like directories, and entries at
+    /// the maximum level can't be expanded.
+    fn new_row(
+        &mut self,
+        entry: Entry<PathBuf>,
+        depth: usize,
+        ancestors: Vec<bool>,
+        is_last: bool,
+        archive: Option<PathBuf>,
+    ) -> Row {
+        let tree = self.tree;
+        let is_max_level = tree.max_level.is_some_and(|max| depth >= max);
+        let is_archive = !is_max_level
+            && self
+                .archives
+                .entry(entry.path().to_path_buf())
+                .or_insert_with(|| tree.read_archive(&entry, depth == 0))
+                .is_some();
+        let archive = if is_archive {
+            Some(entry.path().to_path_buf())
+        } else {
+            archive
+        };
+        let is_directory = !is_max_level
+            && (is_archive || Tree::<P>::is_directory(&entry, self.listing(archive.as_ref())));
+        Row {
+            entry,
+            depth,
+            ancestors,
+            is_last,
+            is_directory,
+            archive,
+            exceeded: None,
+            remaining: None,
+        }
+    }
+
+    /// Gets the listing that entries are read from. `archive` is the path of the
+    /// archive that they're read from, if any.
+    fn listing(&self, archive: Option<&PathBuf>) -> Option<&Listing> {
+        match archive {
+            Some(path) => self.archives.get(path).and_then(Option::as_ref),
+            None => self.tree.root_listing(),
+        }
+    }
+
+    /// Gets the children of the directory, reading them if they haven't been read.
+    /// See [`Self::new_row`] for `archive`.
+    fn load_children(&mut self, entry: &Entry<PathBuf>, archive: Option<&PathBuf>) -> Children {
+        let tree = self.tree;
+        let reveal = self.reveal;
+        let listing = match archive {
+            Some(path) => self.archives.get(path).and_then(Option::as_ref),
+            None => tree.root_listing(),
+        };
+        let path = entry.path().to_path_buf();
+        self.children
+            .entry(path)
+            .or_insert_with_key(|path| {
+                if let Some(count) = tree.exceeded_filelimit(entry, listing) {
+                    return Children {
+                        entries: Vec::new(),
+                        remaining: 0,
+                        exceeded: Some(count),
+                    };
+                }
+                let max_entries = tree.config.max_entries(entry, tree.max_entries);
+                let (entries, remaining, _) =
+                    tree.read_children_with(path, listing, reveal, max_entries);
+                Children {
+                    entries,
+                    remaining,
+                    exceeded: None,
+                }
+            })
+            .clone()
+    }
+
+    /// The number of rows that fit on the screen.
+    fn page_size(&self) -> usize {
+        let (_, height) = terminal::size().unwrap_or((0, 2));
+        usize::from(height.saturating_sub(1)).max(1)
+    }
+
+    /// Draws the visible rows and the status line.
+    fn draw<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: Write,
+    {
+        let page_size = self.page_size();
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + page_size {
+            self.scroll = self.selected + 1 - page_size;
+        }
+
+        let rows = self
+            .rows
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(page_size);
+        for (y, (index, row)) in rows.enumerate() {
+            queue!(writer, MoveTo(0, y as u16), Clear(ClearType::CurrentLine))?;
+            if index == self.selected {
+                queue!(
+                    writer,
+                    SetAttribute(Attribute::Reverse),
+                    Print("›"),
+                    SetAttribute(Attribute::Reset),
+                    Print(" ")
+                )?;
+            } else {
+                queue!(writer, Print("  "))?;
+            }
+            self.write_row(writer, index, row)?;
+        }
+        let drawn = self.rows.len().saturating_sub(self.scroll).min(page_size);
+        queue!(
+            writer,
+            MoveTo(0, drawn as u16),
+            Clear(ClearType::FromCursorDown),
+            MoveTo(0, page_size as u16),
+        )?;
+
+        match self.search {
+            Some(ref search) => queue!(writer, Print(format!("/{search}")))?,
+            None => queue!(
+                writer,
+                SetAttribute(Attribute::Dim),
+                Print(HELP),
+                SetAttribute(Attribute::Reset)
+            )?,
+        }
+        writer.flush()
+    }
+
+    /// Writes a row like the static tree output. `index` is the index of the row, to
+    /// check if its children are shown below it.
+    fn write_row<W>(&self, writer: &mut W, index: usize, row: &Row) -> io::Result<()>
+    where
+        W: Write,
+    {
+        let is_open = row.is_directory && self.expanded.contains(row.entry.path());
+        let has_children = self
+            .rows
+            .get(index + 1)
+            .is_some_and(|next| next.depth > row.depth);
+        if row.depth == 0 {
+            self.tree
+                .write_entry(writer, &row.entry, &[], is_open, has_children)?;
+        } else {
+            self.tree
+                .write_branch(writer, &row.ancestors, row.is_last)?;
+            if let Some(remaining) = row.remaining {
+                return self.tree.write_remaining(writer, remaining);
+            }
+            // NOTE Entries are written with their own position, like in the static
+            //      tree.
+            let mut ancestors = row.ancestors.clone();
+            ancestors.push(row.is_last);
+            self.tree
+                .write_entry(writer, &row.entry, &ancestors, is_open, has_children)?;
+        }
+        if let Some(count) = row.exceeded {
+            self.tree.write_exceeded_filelimit(writer, count)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::ColorChoice;
+    use crate::tree::Builder;
+    use std::fs;
+
+    /// Creates a key press event.
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    /// Gets the file names of the visible rows, excluding the root. Rows showing how
+    /// many children remain are shown as `… N`.
+    fn names<P>(browser: &Browser<P>) -> Vec<String>
+    where
+        P: AsRef<Path>,
+    {
+        browser.rows[1..]
+            .iter()
+            .map(|row| {
+                if let Some(remaining) = row.remaining {
+                    return format!("… {remaining}");
+                }
+                row.entry
+                    .path()
+                    .file_name()
+                    .expect("Rows below the root should have file names")
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_browser_navigation() {
+        let dir = tempfile::tempdir().expect("A temporary directory should be created");
+        fs::create_dir_all(dir.path().join("a/b")).expect("Fixture directories should be created");
+        fs::write(dir.path().join("a/b/c.txt"), "").expect("Fixture files should be created");
+        fs::write(dir.path().join("d.txt"), "").expect("Fixture files should be created");
+        fs::write(dir.path().join(".hidden"), "").expect("Fixture files should be created");
+
+        let tree = Builder::new(dir.path())
+            .color_choice(ColorChoice::Off)
+            .build();
+        let mut browser = Browser::new(&tree);
+        assert_eq!(vec!["a", "d.txt"], names(&browser));
+
+        browser.handle_key(press(KeyCode::Down));
+        browser.handle_key(press(KeyCode::Right));
+        assert_eq!(vec!["a", "b", "d.txt"], names(&browser));
+
+        browser.handle_key(press(KeyCode::Right));
+        browser.handle_key(press(KeyCode::Right));
+        browser.handle_key(press(KeyCode::Right));
+        assert_eq!(vec!["a", "b", "c.txt", "d.txt"], names(&browser));
+        assert_eq!(3, browser.selected);
+
+        browser.handle_key(press(KeyCode::Left));
+        assert_eq!(2, browser.selected);
+        browser.handle_key(press(KeyCode::Left));
+        assert_eq!(vec!["a", "b", "d.txt"], names(&browser));
+
+        browser.handle_key(press(KeyCode::Char('.')));
+        assert_eq!(vec![".hidden", "a", "b", "d.txt"], names(&browser));
+
+        browser.handle_key(press(KeyCode::Char('/')));
+        browser.handle_key(press(KeyCode::Char('D')));
+        browser.handle_key(press(KeyCode::Enter));
+        let exit = browser.handle_key(press(KeyCode::Enter));
+        assert_eq!(Some(Exit::Select(dir.path().join("d.txt"))), exit);
+    }
+
+    #[test]
+    fn test_reveal_keeps_other_filters() {
+        let dir = tempfile::tempdir().expect("A temporary directory should be created");
+        fs::create_dir(dir.path().join(".config")).expect("Fixture directories should be created");
+        fs::write(dir.path().join(".env"), "").expect("Fixture files should be created");
+
+        let tree = Builder::new(dir.path())
+            .color_choice(ColorChoice::Off)
+            .directories_only(true)
+            .build();
+        let mut browser = Browser::new(&tree);
+        assert!(names(&browser).is_empty());
+
+        // NOTE Revealing hidden entries doesn't reveal files when only directories are
+        //      shown.
+        browser.handle_key(press(KeyCode::Char('.')));
+        assert_eq!(vec![".config"], names(&browser));
+    }
+
+    #[test]
+    fn test_browser_limits() {
+        let dir = tempfile::tempdir().expect("A temporary directory should be created");
+        const FILES: [&str; 7] = [
+            "a/b/c.txt",
+            "a/d.txt",
+            "a/i.txt",
+            "a/j.txt",
+            "e/f.txt",
+            "e/g.txt",
+            "h.txt",
+        ];
+        for file in FILES {
+            let path = dir.path().join(file);
+            let parent = path.parent().expect("Fixture files should have a parent");
+            fs::create_dir_all(parent).expect("Fixture directories should be created");
+            fs::write(path, "").expect("Fixture files should be created");
+        }
+
+        let tree = Builder::new(dir.path())
+            .color_choice(ColorChoice::Off)
+            .max_level(2)
+            .max_entries(2)
+            .filelimit(3)
+            .build();
+        let mut browser = Browser::new(&tree);
+        assert_eq!(vec!["a", "e", "… 1"], names(&browser));
+
+        // NOTE `a` has more children than the filelimit.
+        browser.handle_key(press(KeyCode::Down));
+        browser.handle_key(press(KeyCode::Right));
+        assert_eq!(vec!["a", "e", "… 1"], names(&browser));
+        assert_eq!(Some(4), browser.rows[1].exceeded);
+
+        let tree = Builder::new(dir.path())
+            .color_choice(ColorChoice::Off)
+            .max_level(2)
+            .build();
+        let mut browser = Browser::new(&tree);
+        browser.handle_key(press(KeyCode::Down));
+        browser.handle_key(press(KeyCode::Right));
+        assert_eq!(
+            vec!["a", "b", "d.txt", "i.txt", "j.txt", "e", "h.txt"],
+            names(&browser)
+        );
+
+        // NOTE `b` is at the maximum level, so it can't be expanded.
+        browser.handle_key(press(KeyCode::Down));
+        browser.handle_key(press(KeyCode::Right));
+        assert_eq!(
+            vec!["a", "b", "d.txt", "i.txt", "j.txt", "e", "h.txt"],
+            names(&browser)
+        );
+    }
+
+    #[test]
+    fn test_browser_archives() {
+        let dir = tempfile::tempdir().expect("A temporary directory should be created");
+        let archive_path = dir.path().join("files.tar");
+        let file = fs::File::create(&archive_path).expect("The archive should be created");
+        let mut archive = tar::Builder::new(file);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(0);
+        header.set_mode(0o644);
+        archive
+            .append_data(&mut header, "inner/file.txt", io::empty())
+            .expect("The file should be archived");
+        archive.finish().expect("The archive should be written");
+
+        // NOTE The root is always opened if it is an archive.
+        let tree = Builder::new(archive_path.as_path())
+            .color_choice(ColorChoice::Off)
+            .build();
+        let mut browser = Browser::new(&tree);
+        assert_eq!(vec!["inner"], names(&browser));
+        browser.handle_key(press(KeyCode::Down));
+        browser.handle_key(press(KeyCode::Right));
+        assert_eq!(vec!["inner", "file.txt"], names(&browser));
+
+        let tree = Builder::new(dir.path())
+            .color_choice(ColorChoice::Off)
+            .archives(true)
+            .build();
+        let mut browser = Browser::new(&tree);
+        browser.handle_key(press(KeyCode::Down));
+        browser.handle_key(press(KeyCode::Right));
+        assert_eq!(vec!["files.tar", "inner"], names(&browser));
+    }
+}
@@ -0,0 +1,225 @@
+//! Module for language statistics of a tree.
+use super::size::HumanSize;
+use crate::color::{Color, ColorChoice};
+use clap::ValueEnum;
+use gengo::Language;
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// Controls how language statistics are displayed.
+#[derive(Debug, ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum LanguagesMode {
+    /// Show a report after the tree.
+    Report,
+    /// Annotate each directory with its dominant language.
+    Inline,
+    /// Both show a report and annotate directories.
+    All,
+}
+
+impl LanguagesMode {
+    /// Should the report be shown after the tree?
+    #[inline]
+    pub fn is_report(&self) -> bool {
+        matches!(self, Self::Report | Self::All)
+    }
+
+    /// Should directories be annotated with their dominant language?
+    #[inline]
+    pub fn is_inline(&self) -> bool {
+        matches!(self, Self::Inline | Self::All)
+    }
+}
+
+/// The totals for a single language.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LanguageTotals {
+    /// The number of files.
+    pub files: usize,
+    /// The number of bytes.
+    pub bytes: u64,
+}
+
+/// Aggregated language statistics.
+#[derive(Debug, Default, Clone)]
+pub struct LanguageStats {
+    /// The totals for each language.
+    totals: HashMap<Language, LanguageTotals>,
+}
+
+impl LanguageStats {
+    /// The number of characters in the language bar.
+    const BAR_WIDTH: u64 = 50;
+    /// The character used to draw the language bar.
+    const BAR_CHAR: &str = "█";
+
+    /// Creates empty statistics.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a file to the statistics.
+    pub fn add(&mut self, language: Language, bytes: u64) {
+        let totals = self.totals.entry(language).or_default();
+        totals.files += 1;
+        totals.bytes += bytes;
+    }
+
+    /// Merges other statistics into these statistics.
+    pub fn merge(&mut self, other: &Self) {
+        for (language, other) in other.totals.iter() {
+            let totals = self.totals.entry(*language).or_default();
+            totals.files += other.files;
+            totals.bytes += other.bytes;
+        }
+    }
+
+    /// Are there no statistics?
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.totals.is_empty()
+    }
+
+    /// Gets the total number of bytes for all languages.
+    pub fn total_bytes(&self) -> u64 {
+        self.totals.values().map(|totals| totals.bytes).sum()
+    }
+
+    /// Gets the languages sorted from the most bytes to the least. Ties are broken by
+    /// file count and then by name.
+    pub fn sorted(&self) -> Vec<(Language, LanguageTotals)> {
+        let mut sorted = self
+            .totals
+            .iter()
+            .map(|(language, totals)| (*language, *totals))
+            .collect::<Vec<_>>();
+        sorted.sort_by(|(a_lang, a), (b_lang, b)| {
+            b.bytes
+                .cmp(&a.bytes)
+                .then(b.files.cmp(&a.files))
+                .then_with(|| a_lang.name().cmp(b_lang.name()))
+        });
+        sorted
+    }
+
+    /// Gets the language with the most bytes.
+    pub fn dominant(&self) -> Option<Language> {
+        self.sorted().first().map(|(language, _)| *language)
+    }
+
+    /// Writes the language bar and table to the writer.
+    pub fn write_report<W>(&self, writer: &mut W, color_choice: ColorChoice) -> io::Result<()>
+    where
+        W: Write,
+    {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let sorted = self.sorted();
+        let total_bytes = self.total_bytes();
+
+        writeln!(writer)?;
+        self.write_bar(writer, color_choice, &sorted, total_bytes)?;
+        writeln!(writer)?;
+
+        let name_width = sorted
+            .iter()
+            .map(|(language, _)| language.name().chars().count())
+            .max()
+            .unwrap_or_default();
+        for (language, totals) in sorted {
+            let (r, g, b) = language.rgb();
+            color_choice.write_to(writer, "●", Some(Color::Rgb(r, g, b)), None)?;
+            let percent = percentage(totals.bytes, total_bytes);
+            let size = HumanSize(totals.bytes).to_string();
+            let files = if totals.files == 1 { "file" } else { "files" };
+            writeln!(
+                writer,
+                " {:<name_width$}  {percent:>5.1}%  {size:>10}  {:>5} {files}",
+                language.name(),
+                totals.files,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes a bar where each language's length is proportional to its bytes.
+    fn write_bar<W>(
+        &self,
+        writer: &mut W,
+        color_choice: ColorChoice,
+        sorted: &[(Language, LanguageTotals)],
+        total_bytes: u64,
+    ) -> io::Result<()>
+    where
+        W: Write,
+    {
+        if total_bytes == 0 {
+            return Ok(());
+        }
+
+        // NOTE Using cumulative bytes avoids rounding errors adding up across
+        //      languages, so the bar is always the same width.
+        let mut cumulative_bytes = 0;
+        let mut written = 0;
+        for (language, totals) in sorted {
+            cumulative_bytes += totals.bytes;
+            let end = (cumulative_bytes * Self::BAR_WIDTH + total_bytes / 2) / total_bytes;
+            let width = end - written;
+            written = end;
+            if width == 0 {
+                continue;
+            }
+            let (r, g, b) = language.rgb();
+            let bar = Self::BAR_CHAR.repeat(width as usize);
+            color_choice.write_to(writer, bar, Some(Color::Rgb(r, g, b)), None)?;
+        }
+        Ok(())
+    }
+}
+
+/// Calculates a percentage, returning `0` if the total is `0`.
+fn percentage(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        (part as f64 / total as f64) * 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_stats_merge_and_dominant() {
+        let mut a = LanguageStats::new();
+        a.add(Language::Rust, 100);
+        a.add(Language::Python, 60);
+
+        let mut b = LanguageStats::new();
+        b.add(Language::Python, 60);
+
+        a.merge(&b);
+        assert_eq!(Some(Language::Python), a.dominant());
+        assert_eq!(220, a.total_bytes());
+    }
+
+    #[test]
+    fn test_language_stats_bar_width() {
+        let mut stats = LanguageStats::new();
+        stats.add(Language::Rust, 1);
+        stats.add(Language::Python, 1);
+        stats.add(Language::Ruby, 1);
+
+        let mut buf = Vec::new();
+        let sorted = stats.sorted();
+        stats
+            .write_bar(&mut buf, ColorChoice::Off, &sorted, stats.total_bytes())
+            .expect("Writing to a buffer should succeed");
+        let bar = String::from_utf8(buf).expect("The bar should be valid UTF-8");
+        assert_eq!(LanguageStats::BAR_WIDTH as usize, bar.chars().count());
+    }
+}
@@ -0,0 +1,185 @@
+//! Module for building a tree from a list of paths instead of the filesystem.
+use super::entry::Attributes;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Component, Path, PathBuf};
+
+/// A hierarchy of paths, like the output of `find` or `git ls-files`, or the contents
+/// of an archive.
+#[derive(Default, Clone)]
+pub struct Listing {
+    /// The children of each listed directory.
+    ///
+    /// Paths are joined onto the root, so they can be used like paths from the
+    /// filesystem.
+    children: HashMap<PathBuf, BTreeSet<PathBuf>>,
+    /// The known attributes of listed paths. Paths without attributes are read from
+    /// the filesystem.
+    attributes: HashMap<PathBuf, Attributes>,
+}
+
+impl Listing {
+    /// Creates a listing from paths relative to the root.
+    pub fn new<R, I, P>(root: R, paths: I) -> Self
+    where
+        R: AsRef<Path>,
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let root = root.as_ref();
+        let mut listing = Self::default();
+        for path in paths {
+            listing.insert(root, path.as_ref());
+        }
+        listing
+    }
+
+    /// Parses a listing from text with one path per line.
+    ///
+    /// If the text contains a NUL byte, paths are separated by NUL bytes instead (like
+    /// `find -print0`). Empty paths are skipped.
+    pub fn parse<R>(root: R, text: &[u8]) -> Self
+    where
+        R: AsRef<Path>,
+    {
+        let separator = if text.contains(&b'\0') { b'\0' } else { b'\n' };
+        let paths = text
+            .split(|b| *b == separator)
+            .map(|path| path.strip_suffix(b"\r").unwrap_or(path))
+            .filter(|path| !path.is_empty())
+            .map(path_from_bytes);
+        Self::new(root, paths)
+    }
+
+    /// Adds a path relative to the root with known attributes to the listing.
+    ///
+    /// Ancestors of the path that don't have attributes are given the
+    /// `ancestor_attributes`.
+    pub(crate) fn insert_with_attributes<F>(
+        &mut self,
+        root: &Path,
+        path: &Path,
+        attributes: Attributes,
+        ancestor_attributes: F,
+    ) where
+        F: Fn() -> Attributes,
+    {
+        let path = self.insert(root, path);
+        let ancestors = path
+            .ancestors()
+            .skip(1)
+            .take_while(|ancestor| *ancestor != root);
+        for ancestor in ancestors {
+            self.attributes
+                .entry(ancestor.to_path_buf())
+                .or_insert_with(&ancestor_attributes);
+        }
+        if path != root {
+            self.attributes.insert(path, attributes);
+        }
+    }
+
+    /// Adds a path and all of its ancestors to the listing.
+    ///
+    /// Returns the path joined onto the root.
+    pub(crate) fn insert(&mut self, root: &Path, path: &Path) -> PathBuf {
+        let mut parent = root.to_path_buf();
+        // NOTE `./` components would create duplicates of the same path, so they are
+        //      skipped.
+        let components = path
+            .components()
+            .filter(|component| !matches!(component, Component::CurDir));
+        for component in components {
+            let child = parent.join(component);
+            // NOTE This can happen for absolute paths when the root is `/`.
+            if child == parent {
+                continue;
+            }
+            self.children
+                .entry(parent)
+                .or_default()
+                .insert(child.clone());
+            parent = child;
+        }
+        parent
+    }
+
+    /// Gets the children of the path, sorted.
+    pub fn children<P>(&self, path: P) -> impl Iterator<Item = &Path>
+    where
+        P: AsRef<Path>,
+    {
+        self.children
+            .get(path.as_ref())
+            .into_iter()
+            .flatten()
+            .map(PathBuf::as_path)
+    }
+
+    /// Gets the known attributes of the path.
+    #[inline]
+    pub fn attributes<P>(&self, path: P) -> Option<&Attributes>
+    where
+        P: AsRef<Path>,
+    {
+        self.attributes.get(path.as_ref())
+    }
+
+    /// Does the path have children?
+    #[inline]
+    pub fn has_children<P>(&self, path: P) -> bool
+    where
+        P: AsRef<Path>,
+    {
+        self.children.contains_key(path.as_ref())
+    }
+}
+
+/// Converts the bytes of a listed path to a path.
+#[cfg(unix)]
+fn path_from_bytes(path: &[u8]) -> PathBuf {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt as _;
+
+    PathBuf::from(OsStr::from_bytes(path))
+}
+
+/// Converts the bytes of a listed path to a path.
+///
+/// Bytes that aren't valid UTF-8 are replaced.
+#[cfg(not(unix))]
+fn path_from_bytes(path: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(path).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::newlines(b"a/b.txt\na/c.txt\nd.txt\n")]
+    #[case::crlf(b"a/b.txt\r\na/c.txt\r\nd.txt\r\n")]
+    #[case::nul(b"a/b.txt\0a/c.txt\0d.txt\0")]
+    #[case::dot_prefix(b"./a/b.txt\n./a/c.txt\n./d.txt\n")]
+    #[case::find(b".\n./a\n./a/b.txt\n./a/c.txt\n./d.txt\n")]
+    fn test_listing_parse(#[case] text: &[u8]) {
+        let listing = Listing::parse("root", text);
+
+        let root = listing.children("root").collect::<Vec<_>>();
+        assert_eq!(vec![Path::new("root/a"), Path::new("root/d.txt")], root);
+        let a = listing.children("root/a").collect::<Vec<_>>();
+        assert_eq!(
+            vec![Path::new("root/a/b.txt"), Path::new("root/a/c.txt")],
+            a
+        );
+        assert!(!listing.has_children("root/d.txt"));
+    }
+
+    #[test]
+    fn test_listing_absolute_paths() {
+        let listing = Listing::new("/", ["/usr/bin"]);
+
+        let root = listing.children("/").collect::<Vec<_>>();
+        assert_eq!(vec![Path::new("/usr")], root);
+    }
+}
@@ -6,18 +6,40 @@ use crate::git::{
     Git,
     status::{self, Status},
 };
+pub use archive::ArchiveFormat;
 pub use builder::Builder;
-pub use charset::Charset;
+pub use charset::{Charset, CharsetPreset, WidthError};
+pub use compare::{CompareBy, Comparison};
 pub use entry::Entry;
+use entry::attributes::ClassificationOverrides;
+pub use fit::Overflow;
+pub use hyperlink::HyperlinkChoice;
+use languages::LanguageStats;
+pub use languages::LanguagesMode;
+pub use listing::Listing;
 use owo_colors::AnsiColors;
 use owo_colors::OwoColorize;
+use std::collections::HashSet;
+use std::ffi::OsStr;
 use std::fmt::Display;
 use std::io::{self, Write, stdout};
 use std::path::{Path, PathBuf};
+pub use summary::Summary;
+use unicode_width::UnicodeWidthStr;
 
+mod archive;
 mod builder;
 mod charset;
+mod compare;
 pub mod entry;
+mod fit;
+mod hyperlink;
+mod interactive;
+mod languages;
+mod listing;
+mod size;
+mod summary;
+mod watch;
 
 /// Generates a tree.
 pub struct Tree<'git, 'charset, P: AsRef<Path>> {
@@ -39,6 +61,41 @@ pub struct Tree<'git, 'charset, P: AsRef<Path>> {
     icons: config::Icons,
     /// Provides color configuration.
     colors: config::Colors,
+    /// Hide generated files?
+    hide_generated: bool,
+    /// Hide vendored files?
+    hide_vendored: bool,
+    /// Hide documentation files?
+    hide_documentation: bool,
+    /// Controls how language statistics are displayed, if at all.
+    languages: Option<LanguagesMode>,
+    /// Show the summary report after the tree?
+    report: bool,
+    /// Don't descend into directories with more than this many entries.
+    filelimit: Option<usize>,
+    /// Show at most this many entries for each directory.
+    max_entries: Option<usize>,
+    /// Only show directories?
+    directories_only: bool,
+    /// Show each entry's path relative to the root instead of its file name?
+    full_path: bool,
+    /// Indent entries with the charset?
+    indent: bool,
+    /// Read the hierarchy from this listing instead of the filesystem.
+    listing: Option<Listing>,
+    /// Descend into archives?
+    archives: bool,
+    /// Show the merged tree of two directories and their differences instead of the
+    /// filesystem.
+    comparison: Option<Comparison>,
+    /// The width to fit names in, and how names that don't fit are written.
+    fit: Option<(usize, Overflow)>,
+    /// Write entry names as hyperlinks?
+    hyperlinks: bool,
+    /// Paths of entries to highlight, like recently changed entries when watching.
+    highlighted: HashSet<PathBuf>,
+    /// Collects errors raised by configuration callbacks.
+    errors: config::ErrorLog,
 }
 
 impl<'git, 'charset, P> Tree<'git, 'charset, P>
@@ -61,84 +118,489 @@ where
     where
         W: Write,
     {
-        let Ok(entry) = Entry::new(&self.root) else {
-            // HACK We can't read the first entry for some reason, so we'll just print
-            //      it and exit.
-            let path = self.root.as_ref();
-            Self::write_path(writer, path)?;
-            return writeln!(writer);
-        };
-        self.write_depth(writer, entry, 0)?;
+        let summary = self.write_tree(writer)?;
+        self.write_report(writer, &summary)?;
         writer.flush()
     }
 
+    /// Writes the tree without the report after it.
+    ///
+    /// Returns the summary of the written tree, which can be combined with the
+    /// summaries of other trees for [`Self::write_report`].
+    pub fn write_tree<W>(&self, writer: &mut W) -> io::Result<Summary>
+    where
+        W: Write,
+    {
+        let listing = self.root_listing();
+        let entry = match (Entry::new(&self.root), listing) {
+            (Ok(entry), _) => entry,
+            // NOTE The root of a listing doesn't need to exist.
+            (Err(_), Some(_)) => Entry::new_virtual(&self.root, true),
+            (Err(_), None) => {
+                // HACK We can't read the first entry for some reason, so we'll just
+                //      print it and exit.
+                let path = self.root.as_ref();
+                Self::write_path(writer, path)?;
+                writeln!(writer)?;
+                return Ok(Summary::new());
+            }
+        };
+        let summary = self.write_depth(writer, entry, &mut Vec::new(), listing)?;
+        self.check_errors()?;
+        Ok(summary)
+    }
+
+    /// Writes the report for the summary, if enabled, and the language statistics, if
+    /// enabled.
+    pub fn write_report<W>(&self, writer: &mut W, summary: &Summary) -> io::Result<()>
+    where
+        W: Write,
+    {
+        if self.report
+            && let Some(report) = self.config.format_summary(summary)
+        {
+            writeln!(writer)?;
+            writeln!(writer, "{report}")?;
+        }
+        if self.languages.is_some_and(|mode| mode.is_report()) {
+            summary
+                .languages()
+                .write_report(writer, self.color_choice())?;
+        }
+        self.check_errors()
+    }
+
+    /// Fails if a configuration callback raised an error and the error log is strict.
+    fn check_errors(&self) -> io::Result<()> {
+        if self.errors.is_strict() && !self.errors.is_empty() {
+            return Err(io::Error::other(
+                "a configuration callback raised an error (--strict-config)",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Gets the listing to read the hierarchy from instead of the filesystem, if any.
+    fn root_listing(&self) -> Option<&Listing> {
+        self.comparison
+            .as_ref()
+            .map(Comparison::listing)
+            .or(self.listing.as_ref())
+    }
+
     /// Writes the tree at a certain depth to the writer.
-    fn write_depth<W, P2>(&self, writer: &mut W, entry: Entry<P2>, depth: usize) -> io::Result<()>
+    ///
+    /// `ancestors` tracks if each ancestor of the entry (excluding the root) is the
+    /// last child of its parent, so its length is the entry's depth.
+    ///
+    /// If `listing` is set, children are read from it instead of the filesystem.
+    ///
+    /// Returns the summary of the entry and all of its displayed descendants.
+    fn write_depth<W, P2>(
+        &self,
+        writer: &mut W,
+        entry: Entry<P2>,
+        ancestors: &mut Vec<bool>,
+        listing: Option<&Listing>,
+    ) -> io::Result<Summary>
     where
         W: Write,
         P2: AsRef<Path>,
     {
+        // NOTE Strict errors stop the tree at the next entry.
+        self.check_errors()?;
+
         let path = entry.path();
+        let depth = ancestors.len();
+        let is_top = depth == 0;
+        let is_max_level = self.max_level.map(|max| depth >= max).unwrap_or(false);
 
-        // NOTE For the top level, we always print the full path the user specified.
-        self.write_entry(writer, &entry, depth == 0)?;
+        // NOTE Archives are traversed like directories, with their own listing.
+        let archive = if is_max_level {
+            None
+        } else {
+            self.read_archive(&entry, is_top)
+        };
+        let listing = archive.as_ref().or(listing);
 
-        writeln!(writer)?;
-        if !path.is_dir() {
-            return Ok(());
+        if archive.is_none() && !Self::is_directory(&entry, listing) {
+            // NOTE For the top level, we always print the full path the user specified.
+            let statuses = self.write_entry(writer, &entry, ancestors, false, false)?;
+            writeln!(writer)?;
+
+            let mut summary = Summary::new();
+            if !is_top {
+                summary.add_entry(&entry, statuses.untracked, statuses.tracked);
+            }
+            return Ok(summary);
         }
 
-        // NOTE We'll just skip file read errors to continue printing the rest of the
-        //      tree.
-        let entries = match path.read_dir() {
-            Ok(entries) => entries.filter_map(Result::ok),
-            Err(_) => return Ok(()),
+        // NOTE Children are counted before they're read, so that the attributes of
+        //      children in directories that aren't opened aren't read.
+        let exceeded = if is_max_level {
+            None
+        } else {
+            self.exceeded_filelimit(&entry, listing)
         };
-        let entries = {
-            let entries = entries.map(|entry| entry.path()).map(Entry::new);
-            // NOTE If we can't read a directory entry, then we'll just ignore it so that
-            //      we don't stop early.
-            let entries = entries.filter_map(Result::ok);
-
-            // NOTE If the config exists and it successfully detects if a file should
-            //      be skipped, use that value. Otherwise, use default behavior.
-            let entries = entries.filter(|entry| !self.should_skip_entry(entry));
-
-            // NOTE By default entry order is not guaranteed. This explicitly sorts them.
-            // TODO Support different sorting algorithms.
-            let mut entries = entries.collect::<Vec<_>>();
-            entries.sort_by_key(|entry| {
-                let path = entry.path();
-                path.to_path_buf()
-            });
-            entries
+
+        let (statuses, mut summary) = if let Some(count) = exceeded {
+            let statuses = self.write_entry(writer, &entry, ancestors, false, false)?;
+            self.write_exceeded_filelimit(writer, count)?;
+            writeln!(writer)?;
+            (statuses, Summary::new())
+        } else {
+            let max_entries = self.config.max_entries(&entry, self.max_entries);
+            let (children, remaining, skipped) = if is_max_level {
+                (Vec::new(), 0, Summary::new())
+            } else {
+                self.read_children(path, listing, max_entries)
+            };
+            let has_children = !children.is_empty() || remaining > 0;
+
+            if !self.languages.is_some_and(|mode| mode.is_inline()) {
+                let statuses =
+                    self.write_entry(writer, &entry, ancestors, !is_max_level, has_children)?;
+                writeln!(writer)?;
+                let mut summary =
+                    self.write_children(writer, children, remaining, ancestors, listing)?;
+                summary.merge(&skipped);
+                (statuses, summary)
+            } else {
+                // NOTE The dominant language isn't known until the children have been
+                //      traversed, so they are buffered and written after this entry.
+                let mut buf = Vec::new();
+                let mut summary =
+                    self.write_children(&mut buf, children, remaining, ancestors, listing)?;
+                summary.merge(&skipped);
+                let statuses =
+                    self.write_entry(writer, &entry, ancestors, !is_max_level, has_children)?;
+                self.write_dominant_language(writer, summary.languages())?;
+                writeln!(writer)?;
+                writer.write_all(&buf)?;
+                (statuses, summary)
+            }
         };
-        if self.max_level.map(|max| depth >= max).unwrap_or(false) {
-            return Ok(());
+
+        if !is_top {
+            summary.add_entry(&entry, statuses.untracked, statuses.tracked);
         }
+        Ok(summary)
+    }
 
-        for entry in entries {
-            self.write_indentation(writer, depth)?;
-            write!(writer, "{}", self.charset.depth)?;
-            self.write_depth(writer, entry, depth + 1)?;
+    /// Checks if the entry should be traversed as a directory.
+    ///
+    /// When reading from a listing, entries with listed children are directories even
+    /// if they are files on the filesystem.
+    fn is_directory<P2>(entry: &Entry<P2>, listing: Option<&Listing>) -> bool
+    where
+        P2: AsRef<Path>,
+    {
+        let path = entry.path();
+        match listing {
+            Some(listing) => listing.has_children(path) || entry.attributes().is_directory(),
+            None => path.is_dir(),
         }
+    }
 
-        Ok(())
+    /// Gets the number of children of the directory if it has more than the filelimit,
+    /// so it shouldn't be opened. See [`Self::write_depth`] for `listing`.
+    fn exceeded_filelimit<P2>(&self, entry: &Entry<P2>, listing: Option<&Listing>) -> Option<usize>
+    where
+        P2: AsRef<Path>,
+    {
+        let limit = self.config.filelimit(entry, self.filelimit)?;
+        let count = self.read_child_paths(entry.path(), listing).len();
+        (count > limit).then_some(count)
     }
 
-    /// Writes an entry.
-    fn write_entry<W, P2>(&self, writer: &mut W, entry: &Entry<P2>, is_top: bool) -> io::Result<()>
+    /// Reads the entry's contents if it is an archive that should be opened.
+    ///
+    /// The root is always opened if it is an archive. Other archives are only opened
+    /// if archives are enabled. Archives that can't be read are shown like files.
+    fn read_archive<P2>(&self, entry: &Entry<P2>, is_top: bool) -> Option<Listing>
     where
-        W: Write,
         P2: AsRef<Path>,
     {
+        if !(is_top || self.archives) || !entry.attributes().is_file() {
+            return None;
+        }
         let path = entry.path();
-        self.write_statuses(writer, path)?;
+        ArchiveFormat::detect(path)?.read(path).ok()
+    }
+
+    /// Reads the children of a directory that should be displayed, sorted. See
+    /// [`Self::write_depth`] for `listing`.
+    ///
+    /// Only the first `max_entries` displayed children are read. Also returns the
+    /// number of remaining children that weren't read, and a summary of the children
+    /// that were skipped.
+    #[inline]
+    fn read_children(
+        &self,
+        path: &Path,
+        listing: Option<&Listing>,
+        max_entries: Option<usize>,
+    ) -> (Vec<Entry<PathBuf>>, usize, Summary) {
+        self.read_children_with(path, listing, Reveal::default(), max_entries)
+    }
+
+    /// Reads the children of a directory like [`Self::read_children`], but also shows
+    /// skipped entries that `reveal` reveals.
+    fn read_children_with(
+        &self,
+        path: &Path,
+        listing: Option<&Listing>,
+        reveal: Reveal,
+        max_entries: Option<usize>,
+    ) -> (Vec<Entry<PathBuf>>, usize, Summary) {
+        let mut paths = self.read_child_paths(path, listing).into_iter();
+        let max_entries = max_entries.unwrap_or(usize::MAX);
+        let has_sort = self.config.has_sort();
+        // NOTE Sort keys can depend on the attributes, so every child is read before
+        //      the first `max_entries` are taken.
+        let read_limit = if has_sort { usize::MAX } else { max_entries };
+        let mut entries = Vec::new();
+        let mut skipped = Summary::new();
 
-        let icon = self.icons.get_icon(entry);
-        self.write_colorized_for_entry(entry, writer, icon)?;
-        // NOTE Padding for the icons
+        // NOTE If the config exists and it successfully detects if a file should
+        //      be skipped, use that value. Otherwise, use default behavior.
+        while entries.len() < read_limit
+            && let Some(path) = paths.next()
+        {
+            let Some(entry) = self.read_child(path, listing) else {
+                continue;
+            };
+            let entry = self.apply_git_attributes(entry);
+            if self.should_skip_entry(&entry, reveal) {
+                let is_ignored = self.is_path_ignored(entry.path());
+                skipped.add_skipped(&entry, is_ignored);
+            } else {
+                entries.push(entry);
+            }
+        }
+
+        // NOTE The attributes of children that aren't displayed aren't read, so they
+        //      are only counted if the filters that don't need attributes (the
+        //      default rule and `-d`) don't skip them. See
+        //      [`Self::is_remaining_exact`].
+        let mut remaining = 0;
+        for path in paths {
+            let is_directory = Self::is_directory_path(&path, listing);
+            let entry = Entry::new_virtual(path, is_directory);
+            let is_ignored = self.is_path_ignored(entry.path());
+            let is_skipped = reveal.skips_by_default(&entry, || is_ignored)
+                || (self.directories_only && !is_directory);
+            if is_skipped {
+                skipped.add_skipped(&entry, is_ignored);
+            } else {
+                remaining += 1;
+            }
+        }
+
+        if has_sort {
+            let mut keyed = entries
+                .into_iter()
+                .map(|entry| (self.config.sort_key(&entry), entry))
+                .collect::<Vec<_>>();
+            // NOTE The sort is stable, so entries with the same key keep the order of
+            //      their paths.
+            keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
+            entries = keyed.into_iter().map(|(_, entry)| entry).collect();
+            if entries.len() > max_entries {
+                remaining += entries.len() - max_entries;
+                entries.truncate(max_entries);
+            }
+        }
+        (entries, remaining, skipped)
+    }
+
+    /// Checks if the path is a directory, or a symlink to a directory, without reading
+    /// its attributes. See [`Self::write_depth`] for `listing`.
+    fn is_directory_path(path: &Path, listing: Option<&Listing>) -> bool {
+        let Some(listing) = listing else {
+            return path.is_dir();
+        };
+        match listing.attributes(path) {
+            Some(attributes) => attributes.is_directory_or_link(),
+            None => listing.has_children(path) || path.is_dir(),
+        }
+    }
+
+    /// Is the number of children that aren't displayed exact? Children that aren't
+    /// displayed aren't read, so the filters that need their attributes (hiding
+    /// categories and the configuration's `skip` function) can't be applied to them.
+    /// With a `sort` function, all children are read.
+    fn is_remaining_exact(&self) -> bool {
+        let needs_attributes = self.hide_generated
+            || self.hide_vendored
+            || self.hide_documentation
+            || self.config.has_skip();
+        !needs_attributes || self.config.has_sort()
+    }
+
+    /// Reads the paths of a directory's children, sorted, without reading their
+    /// attributes. See [`Self::write_depth`] for `listing`.
+    fn read_child_paths(&self, path: &Path, listing: Option<&Listing>) -> Vec<PathBuf> {
+        let mut paths = match listing {
+            Some(listing) => listing.children(path).map(Path::to_path_buf).collect(),
+            // NOTE We'll just skip file read errors to continue printing the rest of
+            //      the tree.
+            None => path
+                .read_dir()
+                .into_iter()
+                .flatten()
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .collect::<Vec<_>>(),
+        };
+        // NOTE By default entry order is not guaranteed. This explicitly sorts them.
+        //      The configuration's `sort` function can reorder them afterwards.
+        paths.sort();
+        paths
+    }
+
+    /// Reads a child's attributes, from the listing if there is one, or from the
+    /// filesystem.
+    ///
+    /// Listed children with known attributes (e.g. in an archive) use them. Otherwise,
+    /// listed children that exist on the filesystem get their attributes from the
+    /// filesystem, and the rest are virtual entries.
+    fn read_child(&self, path: PathBuf, listing: Option<&Listing>) -> Option<Entry<PathBuf>> {
+        let Some(listing) = listing else {
+            // NOTE If we can't read a directory entry, then we'll just ignore it so
+            //      that we don't stop early.
+            return Entry::new_relative_to(path, &self.root).ok();
+        };
+        if let Some(attributes) = listing.attributes(&path) {
+            return Some(Entry::with_attributes(path, attributes.clone()));
+        }
+        let entry = Entry::new_relative_to(path.clone(), &self.root)
+            .unwrap_or_else(|_| Entry::new_virtual(path.clone(), listing.has_children(&path)));
+        Some(entry)
+    }
+
+    /// Writes the children of a directory to the writer. See [`Self::write_depth`]
+    /// for `ancestors` and `listing`.
+    ///
+    /// If there are `remaining` children that aren't displayed, a line showing how
+    /// many were not displayed is written after the children.
+    ///
+    /// Returns the combined summary of the displayed children.
+    fn write_children<W>(
+        &self,
+        writer: &mut W,
+        children: Vec<Entry<PathBuf>>,
+        remaining: usize,
+        ancestors: &mut Vec<bool>,
+        listing: Option<&Listing>,
+    ) -> io::Result<Summary>
+    where
+        W: Write,
+    {
+        let mut summary = Summary::new();
+        let count = children.len();
+        let has_remaining = remaining > 0;
+
+        for (index, entry) in children.into_iter().enumerate() {
+            // NOTE If some entries aren't displayed, then the line showing how many
+            //      remain is the last child.
+            let is_last = !has_remaining && index + 1 == count;
+            self.write_branch(writer, ancestors, is_last)?;
+
+            ancestors.push(is_last);
+            let child_summary = self.write_depth(writer, entry, ancestors, listing);
+            ancestors.pop();
+            summary.merge(&child_summary?);
+        }
+
+        if has_remaining {
+            self.write_branch(writer, ancestors, true)?;
+            self.write_remaining(writer, remaining)?;
+            writeln!(writer)?;
+        }
+
+        Ok(summary)
+    }
+
+    /// Writes how many children of a directory aren't displayed.
+    fn write_remaining<W>(&self, writer: &mut W, remaining: usize) -> io::Result<()>
+    where
+        W: Write,
+    {
+        let entries = if remaining == 1 { "entry" } else { "entries" };
+        let up_to = if self.is_remaining_exact() {
+            ""
+        } else {
+            "up to "
+        };
+        self.write_dimmed(writer, format!("… {up_to}{remaining} more {entries}"))
+    }
+
+    /// Writes that a directory isn't opened because it has `count` children, which
+    /// exceeds the filelimit.
+    fn write_exceeded_filelimit<W>(&self, writer: &mut W, count: usize) -> io::Result<()>
+    where
+        W: Write,
+    {
+        let message = format!(" [{count} entries exceeds filelimit, not opening dir]");
+        self.write_dimmed(writer, message)
+    }
+
+    /// Writes the dominant language of a directory, if any.
+    fn write_dominant_language<W>(
+        &self,
+        writer: &mut W,
+        language_stats: &LanguageStats,
+    ) -> io::Result<()>
+    where
+        W: Write,
+    {
+        let Some(language) = language_stats.dominant() else {
+            return Ok(());
+        };
+        let (r, g, b) = language.rgb();
         write!(writer, " ")?;
+        self.color_choice().write_to(
+            writer,
+            format!("({})", language.name()),
+            Some(Color::Rgb(r, g, b)),
+            None,
+        )
+    }
+
+    /// Writes an entry. `ancestors` are the entry's ancestors and if it is the last
+    /// child of its parent. See [`Self::write_depth`]. `is_open` is if the entry is
+    /// a directory with its children shown, and `has_children` is if any children
+    /// are written below it.
+    ///
+    /// Returns the git statuses of the entry.
+    fn write_entry<W, P2>(
+        &self,
+        writer: &mut W,
+        entry: &Entry<P2>,
+        ancestors: &[bool],
+        is_open: bool,
+        has_children: bool,
+    ) -> io::Result<GitStatuses>
+    where
+        W: Write,
+        P2: AsRef<Path>,
+    {
+        let is_top = ancestors.is_empty();
+        let path = entry.path();
+        let statuses = self.write_statuses(writer, path)?;
+
+        // NOTE Without icons, the column and its padding are removed.
+        let icon_column_width = if self.icons.icon_set().has_icons() {
+            let icon = self.icons.get_icon(entry, is_open);
+            let icon_width = icon.width();
+            self.write_colorized_for_entry(entry, writer, icon)?;
+            // NOTE Padding for the icons
+            write!(writer, " ")?;
+            icon_width + 1
+        } else {
+            0
+        };
 
         // HACK is_path_ignored tries to strip the prefix, which we never want to do at
         //      the top when the path is *only* the prefix. In fact, we don't want to
@@ -147,24 +609,177 @@ where
         //      should always be a directory, and the current implementation only seems
         //      to work for files.
         let is_ignored = !is_top && self.is_path_ignored(path);
+        // NOTE Generated and vendored files are dimmed like ignored files, since they
+        //      are usually not as interesting.
+        let is_dimmed = is_ignored || entry.is_generated() || entry.is_vendored();
 
-        let path = if is_top {
+        let name = if is_top {
             path.as_os_str()
+        } else if self.full_path {
+            // NOTE Children are always joined onto the root, so the prefix should always
+            //      be strippable.
+            path.strip_prefix(&self.root).unwrap_or(path).as_os_str()
         } else {
-            // NOTE The only time the path shouldn't have a file name is at the top
-            //      level, which could be a path like "." or "..". At the top level
-            //      call, `full_name` should always receive `true`.
+            // NOTE The only time a directory entry shouldn't have a file name is at the
+            //      top level, which could be a path like "." or "..". Paths from a
+            //      listing can also end with components like `/` or `..`, so this
+            //      falls back to the last component.
             path.file_name()
-                .expect("A directory entry should always have a file name")
+                .or_else(|| path.components().next_back().map(|c| c.as_os_str()))
+                .unwrap_or(path.as_os_str())
+        };
+
+        let url = self.hyperlink(entry);
+        let Some((width, overflow)) = self.fit else {
+            self.write_name(writer, entry, name, is_dimmed, url.as_deref())?;
+            return Ok(statuses);
+        };
+
+        // NOTE Nerd Font icons are usually drawn over the padding after them, but
+        //      terminals only move the cursor by their display width.
+        let indent_width = if self.indent {
+            ancestors.len() * self.charset.width()
+        } else {
+            0
         };
+        let prefix_width = self.status_width() + indent_width + icon_column_width;
+        let available = width.saturating_sub(prefix_width);
+        let name = name.to_string_lossy();
+        match overflow {
+            Overflow::Truncate => {
+                let name = fit::truncate(&name, available);
+                self.write_name(writer, entry, name.as_ref(), is_dimmed, url.as_deref())?;
+            }
+            Overflow::Wrap => {
+                for (index, line) in fit::wrap(&name, available).into_iter().enumerate() {
+                    if index > 0 {
+                        writeln!(writer)?;
+                        self.write_continuation(
+                            writer,
+                            ancestors,
+                            icon_column_width,
+                            has_children,
+                        )?;
+                    }
+                    self.write_name(writer, entry, line.as_ref(), is_dimmed, url.as_deref())?;
+                }
+            }
+        }
+        Ok(statuses)
+    }
 
-        if !is_ignored {
-            Self::write_path(writer, path)
+    /// Writes an entry's name, styled and linked.
+    fn write_name<W, P2>(
+        &self,
+        writer: &mut W,
+        entry: &Entry<P2>,
+        name: &OsStr,
+        is_dimmed: bool,
+        url: Option<&str>,
+    ) -> io::Result<()>
+    where
+        W: Write,
+        P2: AsRef<Path>,
+    {
+        if let Some(url) = url {
+            hyperlink::write_start(writer, url)?;
+        }
+        if self.highlighted.contains(entry.path()) {
+            self.write_highlighted(writer, name.display())?;
+        } else if !is_dimmed {
+            Self::write_path(writer, name)?;
         } else {
-            const TEXT_COLOR: Option<Color> = Some(Color::Ansi(AnsiColors::Black));
-            self.color_choice()
-                .write_to(writer, path.display(), TEXT_COLOR, None)
+            self.write_dimmed(writer, name.display())?;
         }
+        if url.is_some() {
+            hyperlink::write_end(writer)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the start of a line that continues a wrapped name, so that the name
+    /// lines up with the previous line.
+    ///
+    /// If the entry `has_children`, their branch is continued under the icon, where
+    /// the children's branches start.
+    fn write_continuation<W>(
+        &self,
+        writer: &mut W,
+        ancestors: &[bool],
+        icon_column_width: usize,
+        has_children: bool,
+    ) -> io::Result<()>
+    where
+        W: Write,
+    {
+        write!(writer, "{:1$}", "", self.status_width())?;
+        if self.indent {
+            self.write_indentation(writer, ancestors)?;
+        }
+        if !(self.indent && has_children) {
+            return write!(writer, "{:1$}", "", icon_column_width);
+        }
+        // NOTE The branch can be wider than the icon column, so it's cut to fit the
+        //      column, like `│` from `│   `.
+        let branch = fit::wrap(&self.charset.breadth, icon_column_width)
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        write!(
+            writer,
+            "{branch}{:1$}",
+            "",
+            icon_column_width.saturating_sub(branch.width())
+        )
+    }
+
+    /// Gets the display width of the statuses written before each entry.
+    fn status_width(&self) -> usize {
+        if self.comparison.is_some() {
+            1
+        } else if self.git.is_some() {
+            2
+        } else {
+            0
+        }
+    }
+
+    /// Writes dimmed text, like the names of ignored files.
+    fn write_dimmed<W, D>(&self, writer: &mut W, display: D) -> io::Result<()>
+    where
+        W: Write,
+        D: Display + OwoColorize,
+    {
+        const TEXT_COLOR: Option<Color> = Some(Color::Ansi(AnsiColors::Black));
+        self.color_choice()
+            .write_to(writer, display, TEXT_COLOR, None)
+    }
+
+    /// Gets the URL to link the entry's name to, if any.
+    fn hyperlink<P2>(&self, entry: &Entry<P2>) -> Option<String>
+    where
+        P2: AsRef<Path>,
+    {
+        if !self.hyperlinks {
+            return None;
+        }
+        let default = hyperlink::file_url(entry.path());
+        // NOTE Control characters could end the hyperlink's escape sequence early.
+        self.config
+            .hyperlink(entry, default)
+            .filter(|url| !url.chars().any(char::is_control))
+    }
+
+    /// Writes highlighted text, like the names of recently changed entries.
+    fn write_highlighted<W, D>(&self, writer: &mut W, display: D) -> io::Result<()>
+    where
+        W: Write,
+        D: Display + OwoColorize,
+    {
+        const TEXT_COLOR: Option<Color> = Some(Color::Ansi(AnsiColors::Black));
+        const BACKGROUND_COLOR: Option<Color> = Some(Color::Ansi(AnsiColors::Yellow));
+        self.color_choice()
+            .write_to(writer, display, TEXT_COLOR, BACKGROUND_COLOR)
     }
 
     /// Writes a path's name.
@@ -177,29 +792,94 @@ where
         writer.write_all(path.as_os_str().as_encoded_bytes())
     }
 
+    /// Writes the indentation and branch for a child of the entry with the
+    /// `ancestors`. See [`Self::write_depth`].
+    ///
+    /// Nothing is written if indentation is disabled.
+    fn write_branch<W>(&self, writer: &mut W, ancestors: &[bool], is_last: bool) -> io::Result<()>
+    where
+        W: Write,
+    {
+        if !self.indent {
+            return Ok(());
+        }
+        self.write_indentation(writer, ancestors)?;
+        let branch = if is_last {
+            &self.charset.last
+        } else {
+            &self.charset.depth
+        };
+        write!(writer, "{branch}")
+    }
+
     /// Writes indentation.
-    fn write_indentation<W>(&self, writer: &mut W, level: usize) -> io::Result<()>
+    ///
+    /// Columns of ancestors that are the last child of their parent have no more
+    /// siblings to connect to, so they are blank.
+    fn write_indentation<W>(&self, writer: &mut W, ancestors: &[bool]) -> io::Result<()>
     where
         W: Write,
     {
-        for _ in 0..level {
-            write!(writer, "{}", self.charset.breadth)?;
+        for is_last in ancestors {
+            let column = if *is_last {
+                &self.charset.indent
+            } else {
+                &self.charset.breadth
+            };
+            write!(writer, "{column}")?;
         }
         Ok(())
     }
 
     /// Checks if an entry should be skipped.
     ///
-    /// If the config exists, the config has a `skip` function, *and* that function
+    /// Entries that are hidden by category (non-directories when only directories are
+    /// shown, generated, vendored, documentation) are always skipped. Otherwise, if
+    /// the config exists, the config has a `skip` function, *and* that function
     /// successfully returns a boolean value, then that value will be used. Otherwise,
-    /// it will just skip all hidden files.
-    fn should_skip_entry<P2>(&self, entry: &Entry<P2>) -> bool
+    /// it will just skip all hidden and git-ignored files, unless `reveal` reveals
+    /// them.
+    fn should_skip_entry<P2>(&self, entry: &Entry<P2>, reveal: Reveal) -> bool
     where
         P2: AsRef<Path>,
     {
+        let is_hidden_category = (self.directories_only
+            && !entry.attributes().is_directory_or_link())
+            || (self.hide_generated && entry.is_generated())
+            || (self.hide_vendored && entry.is_vendored())
+            || (self.hide_documentation && entry.is_documentation());
+        if is_hidden_category {
+            return true;
+        }
+
+        // NOTE Revealing only overrides the default rule, so the configuration can
+        //      still skip revealed entries.
         let path = entry.path();
-        self.config
-            .should_skip(entry, || self.is_path_ignored(path))
+        self.config.should_skip(entry, || {
+            reveal.skips_by_default(entry, || self.is_path_ignored(path))
+        })
+    }
+
+    /// Applies `linguist-*` git attributes to the entry's classification.
+    fn apply_git_attributes<P2>(&self, mut entry: Entry<P2>) -> Entry<P2>
+    where
+        P2: AsRef<Path>,
+    {
+        let Some(git) = self.git else { return entry };
+        if !entry.attributes().is_file() {
+            return entry;
+        }
+        let Some(path) = self.clean_path_for_git2(entry.path()) else {
+            return entry;
+        };
+
+        let overrides = ClassificationOverrides {
+            generated: git.linguist_attribute(&path, "linguist-generated"),
+            vendored: git.linguist_attribute(&path, "linguist-vendored"),
+            documentation: git.linguist_attribute(&path, "linguist-documentation"),
+        };
+        entry.override_classification(overrides);
+        entry
     }
 
     /// Checks if a path is ignored.
@@ -211,9 +891,7 @@ where
             .and_then(|git| {
                 // HACK This function doesn't expect a `./` prefix. It seems to return
                 //      `true` when it's present???
-                let path = self
-                    .clean_path_for_git2(path)
-                    .expect("Should be able to resolve path relative to git root");
+                let path = self.clean_path_for_git2(path)?;
                 git.is_ignored(path).ok()
             })
             .unwrap_or(false)
@@ -243,60 +921,82 @@ where
     }
 
     /// Writes colorized git statuses.
-    fn write_statuses<W>(&self, writer: &mut W, path: &Path) -> io::Result<()>
+    ///
+    /// Returns the statuses that were written.
+    fn write_statuses<W>(&self, writer: &mut W, path: &Path) -> io::Result<GitStatuses>
     where
         W: Write,
     {
-        let Some(git) = self.git else { return Ok(()) };
+        if let Some(ref comparison) = self.comparison {
+            // NOTE Differences reuse the worktree status markers and colors, but they
+            //      aren't git statuses.
+            let status = comparison.status(path);
+            let color = status
+                .and_then(|status| self.colors.for_untracked_git_status(status, path.display()));
+            self.write_status_text(writer, status, color)?;
+            return Ok(GitStatuses::default());
+        }
+
+        let Some(git) = self.git else {
+            return Ok(GitStatuses::default());
+        };
 
         // HACK cached status keys don't have a ./ prefix and git2 apparently doesn't expect it.
-        let path = self
-            .clean_path_for_git2(path)
-            .expect("Should be able to resolve path relative to git root");
+        let git_path = self.clean_path_for_git2(path);
+        let git_path = git_path.as_deref();
 
-        self.write_status::<status::Untracked, _, _>(writer, git, &path)?;
-        self.write_status::<status::Tracked, _, _>(writer, git, path)?;
-        Ok(())
+        let untracked = self.write_status::<status::Untracked, _>(writer, git, path, git_path)?;
+        let tracked = self.write_status::<status::Tracked, _>(writer, git, path, git_path)?;
+        Ok(GitStatuses { untracked, tracked })
     }
 
-    /// Writes a colorized untracked (worktree) git status.
-    fn write_status<S, W, P2>(&self, writer: &mut W, git: &Git, path: P2) -> io::Result<()>
+    /// Writes a colorized untracked (worktree) git status. `git_path` is the entry's
+    /// path relative to the repository. Paths that aren't in the repository have no
+    /// status.
+    ///
+    /// Returns the status that was written.
+    fn write_status<S, W>(
+        &self,
+        writer: &mut W,
+        git: &Git,
+        path: &Path,
+        git_path: Option<&Path>,
+    ) -> io::Result<Option<Status>>
     where
         S: StatusGetter + ColoredStatus,
         W: Write,
-        P2: AsRef<Path>,
+    {
+        let status = git_path.and_then(|path| git.status::<S, _>(path).ok().flatten());
+        let color = status.and_then(|status| S::get_color(&self.colors, status, path));
+        self.write_status_text(writer, status, color)?;
+        Ok(status)
+    }
+
+    /// Writes the marker for a status, or a blank if there is no status.
+    fn write_status_text<W>(
+        &self,
+        writer: &mut W,
+        status: Option<Status>,
+        color: Option<Color>,
+    ) -> io::Result<()>
+    where
+        W: Write,
     {
         const NO_STATUS: &str = " ";
 
-        let status = git.status::<S, _>(path).ok().flatten();
-        let color = status.and_then(|status| S::get_color(&self.colors, status));
-        let status = status.map(|status| status.as_str()).unwrap_or(NO_STATUS);
-        self.color_choice().write_to(writer, status, color, None)
+        let text = status.map(|status| status.as_str()).unwrap_or(NO_STATUS);
+        self.color_choice().write_to(writer, text, color, None)
     }
 
     /// Strips the root path prefix, which is necessary for git tools.
+    ///
+    /// Returns `None` if there is no git state, or if the path doesn't exist or isn't
+    /// in the repository (e.g. paths from a listing).
     fn clean_path_for_git2<P2>(&self, path: P2) -> Option<PathBuf>
     where
         P2: AsRef<Path>,
     {
-        let git_root = self.git.and_then(|git| git.root_dir())?;
-
-        // HACK Git root seems to have `/` separators, which breaks path cleanup on
-        //      Windows. This cleans up the git root so it can be used with
-        //      strip_prefix.
-        #[cfg(windows)]
-        let git_root = git_root
-            .canonicalize()
-            .expect("Git root should exist and non-final components should be directories");
-
-        let path = path.as_ref();
-        let path = path
-            .canonicalize()
-            .expect("Path should exist and non-final components should be directories");
-        let path = path
-            .strip_prefix(git_root)
-            .expect("Path should have the git root as a prefix");
-        Some(path.to_path_buf())
+        self.git.and_then(|git| git.relative_path(path))
     }
 
     /// Gets the color choice to use.
@@ -305,22 +1005,55 @@ where
     }
 }
 
+/// The git statuses of an entry.
+#[derive(Debug, Default, Clone, Copy)]
+struct GitStatuses {
+    /// The untracked (worktree) status.
+    untracked: Option<Status>,
+    /// The tracked (index) status.
+    tracked: Option<Status>,
+}
+
+/// Kinds of skipped entries to show anyway.
+#[derive(Debug, Default, Clone, Copy)]
+struct Reveal {
+    /// Show hidden entries?
+    hidden: bool,
+    /// Show git-ignored entries?
+    ignored: bool,
+}
+
+impl Reveal {
+    /// Is the entry skipped by the default rule, because it's hidden or git-ignored and
+    /// not revealed? `git_helper` checks if the entry is git-ignored.
+    fn skips_by_default<P, F>(&self, entry: &Entry<P>, git_helper: F) -> bool
+    where
+        P: AsRef<Path>,
+        F: FnOnce() -> bool,
+    {
+        (!self.hidden && entry.is_hidden()) || (!self.ignored && git_helper())
+    }
+}
+
 /// Private trait to generalize writing statuses.
 trait ColoredStatus {
-    /// Gets the color for the status.
-    fn get_color(config: &config::Colors, status: Status) -> Option<Color>;
+    /// Gets the color for the status of the entry at the path.
+    fn get_color(config: &config::Colors, status: Status, path: &Path) -> Option<Color>;
 }
 
 impl ColoredStatus for status::Untracked {
     #[inline]
-    fn get_color(config: &config::Colors, status: Status) -> Option<Color> {
-        config.for_untracked_git_status(status)
+    fn get_color(config: &config::Colors, status: Status, path: &Path) -> Option<Color> {
+        config.for_untracked_git_status(status, path.display())
     }
 }
 
 impl ColoredStatus for status::Tracked {
     #[inline]
-    fn get_color(config: &config::Colors, status: Status) -> Option<Color> {
-        config.for_tracked_git_status(status)
+    fn get_color(config: &config::Colors, status: Status, path: &Path) -> Option<Color> {
+        config.for_tracked_git_status(status, path.display())
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,46 @@
+//! Module for displaying file sizes.
+use std::fmt::{self, Display};
+
+/// Displays a number of bytes in a human-readable format (e.g. `1.5 KiB`).
+#[derive(Debug, Clone, Copy)]
+pub struct HumanSize(pub u64);
+
+impl HumanSize {
+    /// The units for each power of 1024.
+    const UNITS: [&'static str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+}
+
+impl Display for HumanSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const STEP: f64 = 1024.0;
+
+        let Self(bytes) = *self;
+        if bytes < 1024 {
+            return write!(f, "{bytes} {}", Self::UNITS[0]);
+        }
+
+        let mut size = bytes as f64;
+        let mut unit = 0;
+        while size >= STEP && unit < Self::UNITS.len() - 1 {
+            size /= STEP;
+            unit += 1;
+        }
+        write!(f, "{size:.1} {}", Self::UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, "0 B")]
+    #[case(1023, "1023 B")]
+    #[case(1024, "1.0 KiB")]
+    #[case(1536, "1.5 KiB")]
+    #[case(5 * 1024 * 1024, "5.0 MiB")]
+    fn test_human_size_display(#[case] bytes: u64, #[case] expected: &str) {
+        assert_eq!(expected, HumanSize(bytes).to_string());
+    }
+}
@@ -0,0 +1,186 @@
+//! Module for the summary of a tree.
+use super::Entry;
+use super::languages::LanguageStats;
+use crate::git::status::Status;
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::path::Path;
+
+/// Totals for the entries displayed in a tree.
+///
+/// The root of the tree is not counted.
+#[derive(Debug, Default, Clone)]
+pub struct Summary {
+    /// The number of displayed directories.
+    directories: usize,
+    /// The number of displayed files.
+    files: usize,
+    /// The total size of the displayed files in bytes.
+    bytes: u64,
+    /// The number of hidden entries that were skipped.
+    hidden: usize,
+    /// The number of git-ignored entries that were skipped.
+    ignored: usize,
+    /// The number of displayed entries for each tracked git status.
+    tracked: HashMap<Status, usize>,
+    /// The number of displayed entries for each untracked git status.
+    untracked: HashMap<Status, usize>,
+    /// The language statistics of the displayed files.
+    languages: LanguageStats,
+}
+
+impl Summary {
+    /// Creates an empty summary.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a displayed entry to the summary.
+    pub fn add_entry<P>(
+        &mut self,
+        entry: &Entry<P>,
+        untracked: Option<Status>,
+        tracked: Option<Status>,
+    ) where
+        P: AsRef<Path>,
+    {
+        let attributes = entry.attributes();
+        if attributes.is_directory_or_link() {
+            self.directories += 1;
+        } else {
+            self.files += 1;
+        }
+
+        if let Some(file) = attributes.file() {
+            self.bytes += file.size();
+            if let Some(language) = file.language() {
+                self.languages.add(language, file.size());
+            }
+        }
+
+        if let Some(status) = untracked {
+            *self.untracked.entry(status).or_default() += 1;
+        }
+        if let Some(status) = tracked {
+            *self.tracked.entry(status).or_default() += 1;
+        }
+    }
+
+    /// Adds an entry that was skipped to the summary.
+    pub fn add_skipped<P>(&mut self, entry: &Entry<P>, is_ignored: bool)
+    where
+        P: AsRef<Path>,
+    {
+        if entry.is_hidden() {
+            self.hidden += 1;
+        }
+        if is_ignored {
+            self.ignored += 1;
+        }
+    }
+
+    /// Merges another summary into this summary.
+    pub fn merge(&mut self, other: &Self) {
+        self.directories += other.directories;
+        self.files += other.files;
+        self.bytes += other.bytes;
+        self.hidden += other.hidden;
+        self.ignored += other.ignored;
+        for (status, count) in other.tracked.iter() {
+            *self.tracked.entry(*status).or_default() += count;
+        }
+        for (status, count) in other.untracked.iter() {
+            *self.untracked.entry(*status).or_default() += count;
+        }
+        self.languages.merge(&other.languages);
+    }
+
+    /// The number of displayed directories.
+    #[inline]
+    pub fn directories(&self) -> usize {
+        self.directories
+    }
+
+    /// The number of displayed files.
+    #[inline]
+    pub fn files(&self) -> usize {
+        self.files
+    }
+
+    /// The total size of the displayed files in bytes.
+    #[inline]
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// The number of hidden entries that were skipped.
+    #[inline]
+    pub fn hidden(&self) -> usize {
+        self.hidden
+    }
+
+    /// The number of git-ignored entries that were skipped.
+    #[inline]
+    pub fn ignored(&self) -> usize {
+        self.ignored
+    }
+
+    /// The number of displayed entries with the tracked git status.
+    #[inline]
+    pub fn tracked(&self, status: Status) -> usize {
+        self.tracked.get(&status).copied().unwrap_or_default()
+    }
+
+    /// The number of displayed entries with the untracked git status.
+    #[inline]
+    pub fn untracked(&self, status: Status) -> usize {
+        self.untracked.get(&status).copied().unwrap_or_default()
+    }
+
+    /// The language statistics of the displayed files.
+    #[inline]
+    pub fn languages(&self) -> &LanguageStats {
+        &self.languages
+    }
+}
+
+impl Display for Summary {
+    /// Displays the summary like GNU `tree` (e.g. `1 directory, 2 files`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let directories = if self.directories == 1 {
+            "directory"
+        } else {
+            "directories"
+        };
+        let files = if self.files == 1 { "file" } else { "files" };
+        write!(
+            f,
+            "{} {directories}, {} {files}",
+            self.directories, self.files
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, 0, "0 directories, 0 files")]
+    #[case(1, 1, "1 directory, 1 file")]
+    #[case(2, 3, "2 directories, 3 files")]
+    fn test_summary_display(
+        #[case] directories: usize,
+        #[case] files: usize,
+        #[case] expected: &str,
+    ) {
+        let summary = Summary {
+            directories,
+            files,
+            ..Default::default()
+        };
+        assert_eq!(expected, summary.to_string());
+    }
+}
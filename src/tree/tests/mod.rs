@@ -0,0 +1,279 @@
+use super::entry::Attributes;
+use super::{Builder, Charset, Listing, Overflow};
+use crate::config::{IconSet, Icons};
+use rstest::rstest;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// The builder used for test cases.
+type TestBuilder = Builder<'static, 'static, PathBuf>;
+
+/// Modifies the builder for a test case.
+type Configure = fn(TestBuilder) -> TestBuilder;
+
+/// Placeholder for the temporary root directory in snapshots.
+const ROOT: &str = "<root>";
+
+/// Creates the directory structure used by the snapshots.
+fn fixture() -> TempDir {
+    const FILES: [&str; 5] = ["a/b/c.txt", "a/d.txt", "e/f.txt", "g.txt", "h/i/j/k.txt"];
+
+    let dir = tempfile::tempdir().expect("A temporary directory should be created");
+    for file in FILES {
+        let path = dir.path().join(file);
+        let parent = path.parent().expect("Fixture files should have a parent");
+        fs::create_dir_all(parent).expect("Fixture directories should be created");
+        fs::write(path, "").expect("Fixture files should be created");
+    }
+    dir
+}
+
+/// Renders the tree at the root without colors.
+fn render<F>(root: &Path, configure: F) -> String
+where
+    F: FnOnce(TestBuilder) -> TestBuilder,
+{
+    let builder = Builder::new(root.to_path_buf())
+        .color_choice(crate::color::ColorChoice::Off)
+        .report(false);
+    let tree = configure(builder).build();
+
+    let mut buf = Vec::new();
+    tree.write(&mut buf)
+        .expect("Writing to a buffer should succeed");
+    let rendered = String::from_utf8(buf).expect("The tree should be valid UTF-8");
+    rendered.replacen(&root.display().to_string(), ROOT, 1)
+}
+
+#[rstest]
+#[case::standard(|builder: TestBuilder| builder, include_str!("./snapshots/standard.txt"))]
+#[case::max_level(|builder: TestBuilder| builder.max_level(1), include_str!("./snapshots/max_level.txt"))]
+#[case::max_entries(|builder: TestBuilder| builder.max_entries(2), include_str!("./snapshots/max_entries.txt"))]
+#[case::directories_only(
+    |builder: TestBuilder| builder.directories_only(true),
+    include_str!("./snapshots/directories_only.txt"),
+)]
+#[case::directories_only_max_entries(
+    |builder: TestBuilder| builder.directories_only(true).max_entries(2),
+    include_str!("./snapshots/directories_only_max_entries.txt"),
+)]
+#[case::no_indent(|builder: TestBuilder| builder.indent(false), include_str!("./snapshots/no_indent.txt"))]
+#[case::empty_charset(
+    |builder: TestBuilder| builder.charset(Charset::EMPTY),
+    include_str!("./snapshots/empty_charset.txt"),
+)]
+#[case::ascii_charset(
+    |builder: TestBuilder| builder.charset(Charset::ASCII),
+    include_str!("./snapshots/ascii_charset.txt"),
+)]
+#[case::report(|builder: TestBuilder| builder.report(true), include_str!("./snapshots/report.txt"))]
+#[case::ascii_icons(
+    |builder: TestBuilder| builder.icons(Icons::default().with_icon_set(IconSet::Ascii)),
+    include_str!("./snapshots/ascii_icons.txt"),
+)]
+#[case::no_icons(
+    |builder: TestBuilder| builder.icons(Icons::default().with_icon_set(IconSet::None)),
+    include_str!("./snapshots/no_icons.txt"),
+)]
+fn test_tree_layout(#[case] configure: Configure, #[case] expected: &str) {
+    let dir = fixture();
+    let actual = render(dir.path(), configure);
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn test_tree_listing() {
+    const LISTING: &[u8] = b"./a/d.txt\ng.txt\nmissing/file.txt\nmissing.txt\n";
+
+    let dir = fixture();
+    let listing = Listing::parse(dir.path(), LISTING);
+    let actual = render(dir.path(), |builder| builder.listing(listing));
+    assert_eq!(include_str!("./snapshots/listing.txt"), actual);
+}
+
+#[test]
+fn test_tree_archive() {
+    let dir = fixture();
+    let archive_dir = tempfile::tempdir().expect("A temporary directory should be created");
+    let archive_path = archive_dir.path().join("fixture.tar");
+
+    let file = fs::File::create(&archive_path).expect("The archive should be created");
+    let mut archive = tar::Builder::new(file);
+    archive
+        .append_dir_all("fixture", dir.path())
+        .expect("The fixture should be archived");
+    archive.finish().expect("The archive should be written");
+
+    let actual = render(&archive_path, |builder| builder);
+    assert_eq!(include_str!("./snapshots/archive.txt"), actual);
+}
+
+#[test]
+fn test_tree_directories_only_keeps_directory_symlinks() {
+    let archive_dir = tempfile::tempdir().expect("A temporary directory should be created");
+    let archive_path = archive_dir.path().join("links.tar");
+
+    let file = fs::File::create(&archive_path).expect("The archive should be created");
+    let mut archive = tar::Builder::new(file);
+    for (name, target) in [("dir-link", "e"), ("file-link", "g.txt")] {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        archive
+            .append_link(&mut header, name, target)
+            .expect("The symlink should be archived");
+    }
+    archive
+        .append_dir_all(".", fixture().path())
+        .expect("The fixture should be archived");
+    archive.finish().expect("The archive should be written");
+
+    let actual = render(&archive_path, |builder| {
+        builder.directories_only(true).max_level(1)
+    });
+    assert!(actual.contains("dir-link"), "{actual}");
+    assert!(!actual.contains("file-link"), "{actual}");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_tree_directories_only_keeps_filesystem_directory_symlinks() {
+    let dir = fixture();
+    for (name, target) in [("dir-link", "e"), ("file-link", "g.txt")] {
+        std::os::unix::fs::symlink(target, dir.path().join(name))
+            .expect("The symlink should be created");
+    }
+    let attributes = Attributes::new_relative_to(dir.path().join("dir-link"), dir.path())
+        .expect("The symlink's attributes should be read");
+    assert!(attributes.is_symlink());
+    assert!(attributes.is_directory_or_link());
+
+    let actual = render(dir.path(), |builder| {
+        builder.directories_only(true).max_level(1)
+    });
+    assert!(actual.contains("dir-link"), "{actual}");
+    assert!(!actual.contains("file-link"), "{actual}");
+}
+
+#[test]
+fn test_tree_linguist_overrides() {
+    let dir = tempfile::tempdir().expect("A temporary directory should be created");
+    git2::Repository::init(dir.path()).expect("The repository should be created");
+    let gitattributes = "gen.rs linguist-generated\nthird_party/** linguist-vendored\n";
+    fs::write(dir.path().join(".gitattributes"), gitattributes)
+        .expect("The attributes should be written");
+    fs::create_dir(dir.path().join("third_party")).expect("Fixture directories should be created");
+    for file in ["gen.rs", "main.rs", "third_party/lib.rs"] {
+        fs::write(dir.path().join(file), "").expect("Fixture files should be created");
+    }
+    let git = crate::git::Git::new(dir.path())
+        .expect("The repository should be readable")
+        .expect("The repository should exist");
+
+    let tree = Builder::new(dir.path())
+        .color_choice(crate::color::ColorChoice::Off)
+        .git(&git)
+        .hide_generated(true)
+        .hide_vendored(true)
+        .build();
+    let mut buf = Vec::new();
+    tree.write(&mut buf)
+        .expect("Writing to a buffer should succeed");
+    let actual = String::from_utf8(buf).expect("The tree should be valid UTF-8");
+    assert!(!actual.contains("gen.rs"), "{actual}");
+    assert!(!actual.contains("lib.rs"), "{actual}");
+    assert!(actual.contains("main.rs"), "{actual}");
+}
+
+#[rstest]
+#[case::truncate(Overflow::Truncate, include_str!("./snapshots/truncate.txt"))]
+#[case::wrap(Overflow::Wrap, include_str!("./snapshots/wrap.txt"))]
+fn test_tree_fit_to_width(#[case] overflow: Overflow, #[case] expected: &str) {
+    const LISTING: &str = "a-long-directory/a-very-long-file-name.txt\n\
+                              a-long-directory/short.txt\n\
+                              日本語のファイル名.txt\n";
+
+    let listing = Listing::parse("root", LISTING.as_bytes());
+    let actual = render(Path::new("root"), |builder| {
+        builder.listing(listing).fit_to_width(20, overflow)
+    });
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn test_tree_hyperlink() {
+    let dir = fixture();
+    let actual = render(dir.path(), |builder| {
+        builder.hyperlink(super::HyperlinkChoice::Always)
+    });
+    let url = super::hyperlink::file_url(dir.path().join("g.txt"))
+        .expect("The fixture file should have a URL");
+    let expected = format!("\x1b]8;;{url}\x1b\\g.txt\x1b]8;;\x1b\\");
+    assert!(actual.contains(&expected));
+}
+
+#[rstest]
+#[case::max_entries(|builder: TestBuilder| builder.max_level(1).max_entries(1), 1)]
+#[case::filelimit(|builder: TestBuilder| builder.filelimit(1), 0)]
+fn test_tree_reads_displayed_children(#[case] configure: Configure, #[case] expected: i64) {
+    const CONFIG: &str = r#"
+        return {
+            skip = function(path, attributes, default)
+                reads = (reads or 0) + 1
+                return default
+            end,
+        }
+    "#;
+
+    let dir = fixture();
+    let lua = mlua::Lua::new();
+    let config = lua
+        .load(CONFIG)
+        .eval::<crate::config::Main>()
+        .expect("The config should load");
+    render(dir.path(), |builder| configure(builder.config(config)));
+
+    let reads = lua
+        .globals()
+        .get::<Option<i64>>("reads")
+        .expect("The count should be a number");
+    assert_eq!(expected, reads.unwrap_or(0));
+}
+
+#[test]
+fn test_tree_sort() {
+    const CONFIG: &str = r#"
+        return {
+            sort = function(path, attributes, default)
+                local prefix = attributes.file_type == "directory" and "1" or "0"
+                return prefix .. default
+            end,
+        }
+    "#;
+
+    let dir = fixture();
+    let lua = mlua::Lua::new();
+    let config = lua
+        .load(CONFIG)
+        .eval::<crate::config::Main>()
+        .expect("The config should load");
+    let actual = render(dir.path(), |builder| {
+        builder.config(config).max_level(1).max_entries(2)
+    });
+    assert_eq!(include_str!("./snapshots/sort.txt"), actual);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_tree_skips_fifos() {
+    let dir = fixture();
+    let status = std::process::Command::new("mkfifo")
+        .arg(dir.path().join("fifo"))
+        .status()
+        .expect("mkfifo should run");
+    assert!(status.success(), "The FIFO should be created");
+
+    let actual = render(dir.path(), |builder| builder);
+    assert_eq!(include_str!("./snapshots/standard.txt"), actual);
+}
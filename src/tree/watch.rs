@@ -0,0 +1,218 @@
+//! Module for re-writing the tree when the filesystem changes.
+use super::Tree;
+use crossterm::cursor::MoveTo;
+use crossterm::execute;
+use crossterm::terminal::{Clear, ClearType};
+use notify::{Config, Event, EventKind, PollWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::io::{self, Write, stdout};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::{Duration, Instant};
+
+/// How long to wait for more changes before re-writing the tree.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// How long changed entries are highlighted.
+const HIGHLIGHT: Duration = Duration::from_secs(2);
+
+/// How often to check for changes when native filesystem events aren't available.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Filesystem events sent by a watcher.
+type Events = Receiver<notify::Result<Event>>;
+
+impl<'git, 'charset, P> Tree<'git, 'charset, P>
+where
+    P: AsRef<Path>,
+{
+    /// Writes the tree to stdout, and re-writes it whenever entries under the root are
+    /// created, deleted, renamed, or modified. Changed entries are briefly highlighted.
+    ///
+    /// This only returns if watching fails.
+    pub fn watch(&mut self) -> crate::Result<()> {
+        let (sender, events) = mpsc::channel();
+        let root = self.root.as_ref();
+        let _watcher = watch_path(root, sender)?;
+        // NOTE Events have absolute paths, but entries' paths start with the root, which
+        //      can be relative, like `.`.
+        let canonical_root = root.canonicalize()?;
+        let mut changed = HashMap::<PathBuf, Instant>::new();
+        let mut stdout = stdout();
+        loop {
+            let now = Instant::now();
+            changed.retain(|_, time| now.duration_since(*time) < HIGHLIGHT);
+            self.highlighted = changed.keys().cloned().collect();
+            self.rewrite(&mut stdout)?;
+
+            // NOTE While entries are highlighted, the tree is re-written when their
+            //      highlights expire.
+            let deadline = changed.values().map(|time| *time + HIGHLIGHT).min();
+            let Some(paths) = wait_for_changes(&events, deadline)? else {
+                continue;
+            };
+            if let Some(git) = self.git {
+                git.reload_statuses()?;
+            }
+            let now = Instant::now();
+            changed.extend(paths.into_iter().map(|path| {
+                let path = under_root(self.root.as_ref(), &canonical_root, path);
+                (path, now)
+            }));
+        }
+    }
+
+    /// Clears the screen and writes the tree.
+    fn rewrite<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: Write,
+    {
+        // NOTE The tree is written to a buffer first, so that the screen isn't left
+        //      blank while entries are read.
+        let mut buffer = Vec::new();
+        self.write(&mut buffer)?;
+        execute!(writer, MoveTo(0, 0), Clear(ClearType::All))?;
+        writer.write_all(&buffer)?;
+        writer.flush()
+    }
+}
+
+/// Watches the path recursively with native filesystem events (like inotify on Linux),
+/// falling back to polling.
+fn watch_path(
+    path: &Path,
+    sender: Sender<notify::Result<Event>>,
+) -> notify::Result<Box<dyn Watcher>> {
+    let native = notify::recommended_watcher(sender.clone()).and_then(|mut watcher| {
+        watcher.watch(path, RecursiveMode::Recursive)?;
+        Ok(watcher)
+    });
+    if let Ok(watcher) = native {
+        return Ok(Box::new(watcher));
+    }
+
+    // NOTE Native events can be unavailable, like when the inotify watch limit is
+    //      reached.
+    let config = Config::default().with_poll_interval(POLL_INTERVAL);
+    let mut watcher = PollWatcher::new(sender, config)?;
+    watcher.watch(path, RecursiveMode::Recursive)?;
+    Ok(Box::new(watcher))
+}
+
+/// Maps a changed path back under the root as it was given, so that it matches the
+/// paths of the tree's entries. Paths that aren't under the root are kept.
+fn under_root(root: &Path, canonical_root: &Path, path: PathBuf) -> PathBuf {
+    match path.strip_prefix(canonical_root) {
+        Ok(relative) if relative.as_os_str().is_empty() => root.to_path_buf(),
+        Ok(relative) => root.join(relative),
+        Err(_) => path,
+    }
+}
+
+/// Waits for changes, until there haven't been any more changes for the debounce
+/// duration.
+///
+/// Returns the changed paths, or `None` if the deadline passes before any changes.
+fn wait_for_changes(
+    events: &Events,
+    deadline: Option<Instant>,
+) -> crate::Result<Option<Vec<PathBuf>>> {
+    let mut paths = Vec::new();
+    loop {
+        let timeout = if paths.is_empty() {
+            deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()))
+        } else {
+            Some(DEBOUNCE)
+        };
+        let event = match timeout {
+            Some(timeout) => events.recv_timeout(timeout),
+            None => events.recv().map_err(RecvTimeoutError::from),
+        };
+        match event {
+            // NOTE Reading files (including writing the tree) isn't a change.
+            Ok(event) => {
+                let event = event?;
+                if !matches!(event.kind, EventKind::Access(_)) {
+                    paths.extend(event.paths);
+                }
+            }
+            Err(RecvTimeoutError::Timeout) if paths.is_empty() => return Ok(None),
+            Err(RecvTimeoutError::Timeout) => return Ok(Some(paths)),
+            Err(err @ RecvTimeoutError::Disconnected) => return Err(io::Error::other(err).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::ColorChoice;
+    use crate::tree::Builder;
+    use notify::event::{AccessKind, CreateKind, RemoveKind};
+
+    /// Creates an event for the path.
+    fn event(kind: EventKind, path: &str) -> notify::Result<Event> {
+        Ok(Event::new(kind).add_path(PathBuf::from(path)))
+    }
+
+    #[test]
+    fn test_wait_for_changes_debounces() {
+        let (sender, events) = mpsc::channel();
+        sender
+            .send(event(EventKind::Create(CreateKind::File), "a.txt"))
+            .expect("The event should be sent");
+        sender
+            .send(event(EventKind::Access(AccessKind::Any), "b.txt"))
+            .expect("The event should be sent");
+        sender
+            .send(event(EventKind::Remove(RemoveKind::File), "c.txt"))
+            .expect("The event should be sent");
+
+        let paths = wait_for_changes(&events, None).expect("Waiting for changes should succeed");
+        let expected = vec![PathBuf::from("a.txt"), PathBuf::from("c.txt")];
+        assert_eq!(Some(expected), paths);
+    }
+
+    #[test]
+    fn test_wait_for_changes_deadline() {
+        let (sender, events) = mpsc::channel();
+        sender
+            .send(event(EventKind::Access(AccessKind::Any), "a.txt"))
+            .expect("The event should be sent");
+
+        let paths = wait_for_changes(&events, Some(Instant::now()))
+            .expect("Waiting for changes should succeed");
+        assert_eq!(None, paths);
+    }
+
+    #[test]
+    fn test_relative_root_highlights_changed_entry() {
+        // NOTE Tests run in the crate's directory, so `src` is a relative root.
+        let root = PathBuf::from("src");
+        let canonical_root = root
+            .canonicalize()
+            .expect("The root should be canonicalized");
+        let mut tree = Builder::new(root.clone())
+            .color_choice(ColorChoice::On)
+            .max_level(1)
+            .build();
+        tree.highlighted = [under_root(
+            &root,
+            &canonical_root,
+            canonical_root.join("main.rs"),
+        )]
+        .into_iter()
+        .collect();
+
+        let mut buf = Vec::new();
+        tree.write(&mut buf)
+            .expect("Writing to a buffer should succeed");
+        let mut highlighted = Vec::new();
+        tree.write_highlighted(&mut highlighted, "main.rs")
+            .expect("Writing to a buffer should succeed");
+        let rendered = String::from_utf8(buf).expect("The tree should be valid UTF-8");
+        let highlighted = String::from_utf8(highlighted).expect("The name should be valid UTF-8");
+        assert_ne!("main.rs", highlighted);
+        assert!(rendered.contains(&highlighted), "{rendered}");
+    }
+}